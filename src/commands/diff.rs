@@ -0,0 +1,89 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use clap::Args;
+use json_parser::JsonPatch;
+use json_parser::patch::PatchOperation;
+
+use super::ParserArgs;
+
+#[derive(Args)]
+pub struct DiffArgs {
+    from: PathBuf,
+
+    to: PathBuf,
+
+    #[arg(
+        short,
+        long,
+        help = "Prints an RFC 6902 json patch that turns the first file into the second"
+    )]
+    patch: bool,
+
+    #[arg(short, long, help = "Prints the patch on a single line")]
+    compact: bool,
+
+    #[command(flatten)]
+    parser: ParserArgs,
+}
+
+pub fn run(args: DiffArgs) -> ExitCode {
+    match diff(&args) {
+        Ok(output) => {
+            if !output.is_empty() {
+                println!("{output}");
+            }
+
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!(
+                "Failed to diff {} and {}: {e}",
+                args.from.display(),
+                args.to.display()
+            );
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn diff(args: &DiffArgs) -> Result<String, String> {
+    let read = |file: &Path| fs::read(file).map_err(|e| format!("Error reading file: {e}"));
+    let (from_input, to_input) = (read(&args.from)?, read(&args.to)?);
+
+    let from = super::parse(
+        &from_input,
+        &args.from.display().to_string(),
+        args.parser.options(),
+    )?;
+    let to = super::parse(
+        &to_input,
+        &args.to.display().to_string(),
+        args.parser.options(),
+    )?;
+
+    let patch = JsonPatch::diff(&from, &to);
+
+    if args.patch {
+        return Ok(if args.compact {
+            patch.to_string()
+        } else {
+            format!("{patch:#}")
+        });
+    }
+
+    // Otherwise print one line per change, marked like a unified diff.
+    let lines: Vec<_> = patch
+        .operations()
+        .iter()
+        .map(|operation| match operation {
+            PatchOperation::Add { path, value } => format!("+ {path}: {value}"),
+            PatchOperation::Remove { path } => format!("- {path}"),
+            PatchOperation::Replace { path, value } => format!("~ {path}: {value}"),
+            other => unreachable!("diffs only add, remove and replace, found {}", other.name()),
+        })
+        .collect();
+
+    Ok(lines.join("\n"))
+}
@@ -0,0 +1,105 @@
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use clap::Args;
+use json_parser::serializer::{Indent, Serializer, SerializerOptions};
+
+use super::ParserArgs;
+
+#[derive(Args)]
+pub struct FormatArgs {
+    #[arg(required = true)]
+    files: Vec<PathBuf>,
+
+    #[arg(
+        short,
+        long,
+        help = "Rewrites files in place instead of printing to stdout"
+    )]
+    write: bool,
+
+    #[arg(
+        long,
+        default_value_t = 2,
+        help = "Spaces, or tabs with --tabs, per indentation level"
+    )]
+    indent: usize,
+
+    #[arg(long, help = "Indents with tabs instead of spaces")]
+    tabs: bool,
+
+    #[arg(long, help = "Orders object members by key")]
+    sort_keys: bool,
+
+    #[arg(
+        long,
+        default_value_t = 80,
+        help = "Keeps arrays and objects that fit within this many columns on one line, 0 always expands them"
+    )]
+    max_width: usize,
+
+    #[arg(long, help = "Omits the newline at the end of each file")]
+    no_trailing_newline: bool,
+
+    #[arg(long, help = "Escapes all non-ASCII characters")]
+    ascii: bool,
+
+    #[command(flatten)]
+    parser: ParserArgs,
+}
+
+pub fn run(args: FormatArgs) -> ExitCode {
+    let options = SerializerOptions {
+        escape_non_ascii: args.ascii,
+        sort_keys: args.sort_keys,
+        indent: Some(if args.tabs {
+            Indent::Tabs(args.indent)
+        } else {
+            Indent::Spaces(args.indent)
+        }),
+        max_width: (args.max_width > 0).then_some(args.max_width),
+        trailing_newline: !args.no_trailing_newline,
+    };
+
+    let mut failed = false;
+
+    for file in &args.files {
+        if let Err(e) = format_file(file, &args, &options) {
+            eprintln!("Failed to format {}: {e}", file.display());
+            failed = true;
+        }
+    }
+
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+fn format_file(file: &Path, args: &FormatArgs, options: &SerializerOptions) -> Result<(), String> {
+    let input = fs::read(file).map_err(|e| format!("Error reading file: {e}"))?;
+    let value = super::parse(&input, &file.display().to_string(), args.parser.options())?;
+
+    let mut serializer = Serializer::with_options(Vec::new(), options.clone());
+    serializer
+        .serialize(&value)
+        .map_err(|e| format!("Error formatting file: {e}"))?;
+    let output = serializer.into_inner();
+
+    if !args.write {
+        return io::stdout()
+            .write_all(&output)
+            .map_err(|e| format!("Error writing to stdout: {e}"));
+    }
+
+    // Leave files that are already formatted untouched.
+    if output == input {
+        return Ok(());
+    }
+
+    super::replace_file(file, |writer| writer.write_all(&output))
+        .map_err(|e| format!("Error writing file: {e}"))
+}
@@ -0,0 +1,59 @@
+use std::fs;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::Args;
+use json_parser::JsonPointer;
+use json_parser::parser::Value;
+
+use super::ParserArgs;
+
+#[derive(Args)]
+pub struct GetArgs {
+    file: PathBuf,
+
+    #[arg(help = "RFC 6901 json pointer such as /items/0/name, or \"\" for the whole document")]
+    pointer: JsonPointer,
+
+    #[arg(short, long, help = "Prints strings without quotes or escapes")]
+    raw: bool,
+
+    #[arg(short, long, help = "Prints the value on a single line")]
+    compact: bool,
+
+    #[command(flatten)]
+    parser: ParserArgs,
+}
+
+pub fn run(args: GetArgs) -> ExitCode {
+    match get(&args) {
+        Ok(output) => {
+            println!("{output}");
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!(
+                "Failed to read {} from {}: {e}",
+                args.pointer,
+                args.file.display()
+            );
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn get(args: &GetArgs) -> Result<String, String> {
+    let input = fs::read(&args.file).map_err(|e| format!("Error reading file: {e}"))?;
+    let value = super::parse(
+        &input,
+        &args.file.display().to_string(),
+        args.parser.options(),
+    )?;
+    let value = args.pointer.resolve(&value).map_err(|e| e.to_string())?;
+
+    Ok(match value {
+        Value::String(s) if args.raw => s.to_string(),
+        value if args.compact => value.to_string(),
+        value => format!("{value:#}"),
+    })
+}
@@ -0,0 +1,71 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use clap::Args;
+use json_parser::JqFilter;
+use json_parser::parser::Value;
+
+use super::ParserArgs;
+
+#[derive(Args)]
+pub struct JqArgs {
+    #[arg(help = "Filter such as '.items[] | select(.price < 10) | .name'")]
+    filter: JqFilter,
+
+    #[arg(required = true)]
+    files: Vec<PathBuf>,
+
+    #[arg(short, long, help = "Prints strings without quotes or escapes")]
+    raw: bool,
+
+    #[arg(short, long, help = "Prints each value on a single line")]
+    compact: bool,
+
+    #[command(flatten)]
+    parser: ParserArgs,
+}
+
+pub fn run(args: JqArgs) -> ExitCode {
+    let mut failed = false;
+
+    for file in &args.files {
+        match filter_file(file, &args) {
+            Ok(outputs) => {
+                for output in outputs {
+                    println!("{output}");
+                }
+            }
+            Err(e) => {
+                eprintln!("Failed to filter {}: {e}", file.display());
+                failed = true;
+            }
+        }
+    }
+
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+/// Returns each value produced by the filter, formatted for printing.
+fn filter_file(file: &Path, args: &JqArgs) -> Result<Vec<String>, String> {
+    let input = fs::read(file).map_err(|e| format!("Error reading file: {e}"))?;
+    let value = super::parse(&input, &file.display().to_string(), args.parser.options())?;
+
+    let outputs = args
+        .filter
+        .apply(&value)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|value| match value {
+            Value::String(s) if args.raw => s.into_owned(),
+            value if args.compact => value.to_string(),
+            value => format!("{value:#}"),
+        })
+        .collect();
+
+    Ok(outputs)
+}
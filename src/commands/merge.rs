@@ -0,0 +1,64 @@
+use std::fs;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::Args;
+use json_parser::parser::Value;
+
+use super::ParserArgs;
+
+#[derive(Args)]
+pub struct MergeArgs {
+    #[arg(
+        required = true,
+        help = "Base document followed by RFC 7386 merge patches, applied from left to right"
+    )]
+    files: Vec<PathBuf>,
+
+    #[arg(short, long, help = "Prints the merged document on a single line")]
+    compact: bool,
+
+    #[command(flatten)]
+    parser: ParserArgs,
+}
+
+pub fn run(args: MergeArgs) -> ExitCode {
+    match merge(&args) {
+        Ok(output) => {
+            println!("{output}");
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("Failed to merge: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn merge(args: &MergeArgs) -> Result<String, String> {
+    let inputs = args
+        .files
+        .iter()
+        .map(|file| {
+            fs::read(file).map_err(|e| format!("Error reading file {}: {e}", file.display()))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut merged = Value::Null;
+
+    for (i, (file, input)) in args.files.iter().zip(&inputs).enumerate() {
+        let value = super::parse(input, &file.display().to_string(), args.parser.options())?;
+
+        if i == 0 {
+            merged = value;
+        } else {
+            merged.merge_patch(&value);
+        }
+    }
+
+    Ok(if args.compact {
+        merged.to_string()
+    } else {
+        format!("{merged:#}")
+    })
+}
@@ -0,0 +1,75 @@
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use clap::Args;
+use json_parser::parser::MinifyError;
+use json_parser::{Diagnostic, Lexer, Parser};
+
+use super::ParserArgs;
+
+#[derive(Args)]
+pub struct MinifyArgs {
+    #[arg(required = true)]
+    files: Vec<PathBuf>,
+
+    #[arg(
+        short,
+        long,
+        help = "Rewrites files in place instead of printing to stdout"
+    )]
+    write: bool,
+
+    #[command(flatten)]
+    parser: ParserArgs,
+}
+
+pub fn run(args: MinifyArgs) -> ExitCode {
+    let mut failed = false;
+
+    for file in &args.files {
+        if let Err(e) = minify_file(file, &args) {
+            eprintln!("Failed to minify {}: {e}", file.display());
+            failed = true;
+        }
+    }
+
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+fn minify_file(file: &Path, args: &MinifyArgs) -> Result<(), String> {
+    let input = fs::read(file).map_err(|e| format!("Error reading file: {e}"))?;
+    let source_name = file.display().to_string();
+
+    let (tokens, spans) = Lexer::new(&input)
+        .lex_with_spans()
+        .map_err(|e| Diagnostic::from_lexer_error(&e).render(&input, &source_name))?;
+    let mut parser = Parser::with_spans(tokens, spans, &input, args.parser.options());
+
+    let render = |e: MinifyError| match e {
+        MinifyError::Parser(e) => {
+            Diagnostic::from_parser_error(&e, &input).render(&input, &source_name)
+        }
+        MinifyError::Io(e) => format!("Error writing output: {e}"),
+        e @ MinifyError::UnsupportedDuplicateKeys(_) => e.to_string(),
+    };
+
+    if !args.write {
+        // Minify into memory first, so that nothing reaches stdout unless
+        // the whole document is valid.
+        let mut output = Vec::new();
+        parser.minify(&mut output).map_err(render)?;
+        output.push(b'\n');
+
+        return io::stdout()
+            .write_all(&output)
+            .map_err(|e| format!("Error writing to stdout: {e}"));
+    }
+
+    super::replace_file(file, |writer| parser.minify(writer)).map_err(render)
+}
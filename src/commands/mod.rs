@@ -0,0 +1,142 @@
+pub mod diff;
+pub mod format;
+pub mod get;
+pub mod jq;
+pub mod merge;
+pub mod minify;
+pub mod patch;
+pub mod query;
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+use std::{process, thread};
+
+use clap::Args;
+use json_parser::parser::{DEFAULT_MAX_DEPTH, DuplicateKeys, ParserOptions, Value};
+use json_parser::{Diagnostic, Lexer, Parser};
+
+/// Flags that control how input documents are parsed, shared by every
+/// subcommand.
+#[derive(Args)]
+pub struct ParserArgs {
+    #[arg(
+        long,
+        help = "Maximum nesting depth of objects and arrays [default: 128, unlimited with --iterative]"
+    )]
+    max_depth: Option<usize>,
+
+    #[arg(
+        long,
+        help = "Parses without recursion, for very deeply nested documents"
+    )]
+    iterative: bool,
+
+    #[arg(
+        long,
+        default_value = "error",
+        help = "How to handle repeated object keys: error, first-wins, last-wins or keep-all"
+    )]
+    duplicate_keys: DuplicateKeys,
+}
+
+impl ParserArgs {
+    pub fn options(&self) -> ParserOptions {
+        ParserOptions {
+            max_depth: match self.max_depth {
+                Some(max_depth) => Some(max_depth),
+                None if self.iterative => None,
+                None => Some(DEFAULT_MAX_DEPTH),
+            },
+            iterative: self.iterative,
+            duplicate_keys: self.duplicate_keys,
+            ..Default::default()
+        }
+    }
+}
+
+/// Lexes and parses `input`, rendering any error as a diagnostic that names
+/// `source_name`.
+pub fn parse<'a>(
+    input: &'a [u8],
+    source_name: &str,
+    options: ParserOptions,
+) -> Result<Value<'a>, String> {
+    let (tokens, spans) = Lexer::new(input)
+        .lex_with_spans()
+        .map_err(|e| Diagnostic::from_lexer_error(&e).render(input, source_name))?;
+
+    Parser::with_spans(tokens, spans, input, options)
+        .parse()
+        .map_err(|e| Diagnostic::from_parser_error(&e, input).render(input, source_name))
+}
+
+/// Replaces `file` with the output of `write` without ever leaving it half
+/// written. The output goes to a new file in the same directory, which gets
+/// the permissions of `file` and is renamed over it once complete, or removed
+/// if anything fails.
+pub fn replace_file<E: From<io::Error>>(
+    file: &Path,
+    write: impl FnOnce(&mut BufWriter<File>) -> Result<(), E>,
+) -> Result<(), E> {
+    let permissions = fs::metadata(file)?.permissions();
+    let (temp, handle) = create_temp_file(file)?;
+
+    let result = (|| {
+        handle.set_permissions(permissions)?;
+
+        let mut writer = BufWriter::new(handle);
+        write(&mut writer)?;
+        writer.flush()?;
+        fs::rename(&temp, file)?;
+
+        Ok(())
+    })();
+
+    if result.is_err() {
+        let _ = fs::remove_file(&temp);
+    }
+
+    result
+}
+
+/// Creates a file next to `file` under a name that no other file has.
+fn create_temp_file(file: &Path) -> io::Result<(PathBuf, File)> {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+    let name = file.file_name().unwrap_or_default().to_string_lossy();
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.subsec_nanos());
+
+    loop {
+        let count = COUNTER.fetch_add(1, Ordering::Relaxed);
+        let temp = file.with_file_name(format!(".{name}.{}.{nanos:x}.{count}.tmp", process::id()));
+
+        match OpenOptions::new().write(true).create_new(true).open(&temp) {
+            Ok(handle) => return Ok((temp, handle)),
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Builds the pool that processes `jobs` files, using one thread per core
+/// but no more threads than files, or a single thread if `sequential`.
+pub fn thread_pool(sequential: bool, jobs: usize) -> rayon::ThreadPool {
+    let num_threads = if sequential {
+        1
+    } else {
+        thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1)
+            .clamp(1, jobs.max(1))
+    };
+
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(num_threads)
+        .build()
+        .expect("Failed to build thread pool")
+}
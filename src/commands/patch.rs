@@ -0,0 +1,68 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use clap::Args;
+use json_parser::JsonPatch;
+
+use super::ParserArgs;
+
+#[derive(Args)]
+pub struct PatchArgs {
+    #[arg(help = "Document to patch")]
+    document: PathBuf,
+
+    #[arg(
+        help = "RFC 6902 json patch, an array of add, remove, replace, move, copy and test operations"
+    )]
+    patch: PathBuf,
+
+    #[arg(short, long, help = "Prints the patched document on a single line")]
+    compact: bool,
+
+    #[command(flatten)]
+    parser: ParserArgs,
+}
+
+pub fn run(args: PatchArgs) -> ExitCode {
+    match patch(&args) {
+        Ok(output) => {
+            println!("{output}");
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!(
+                "Failed to patch {} with {}: {e}",
+                args.document.display(),
+                args.patch.display()
+            );
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn patch(args: &PatchArgs) -> Result<String, String> {
+    let read = |file: &Path| fs::read(file).map_err(|e| format!("Error reading file: {e}"));
+    let (document_input, patch_input) = (read(&args.document)?, read(&args.patch)?);
+
+    let mut document = super::parse(
+        &document_input,
+        &args.document.display().to_string(),
+        args.parser.options(),
+    )?;
+    let patch = super::parse(
+        &patch_input,
+        &args.patch.display().to_string(),
+        args.parser.options(),
+    )?;
+
+    JsonPatch::from_value(&patch)
+        .and_then(|patch| patch.apply(&mut document))
+        .map_err(|e| e.to_string())?;
+
+    Ok(if args.compact {
+        document.to_string()
+    } else {
+        format!("{document:#}")
+    })
+}
@@ -0,0 +1,86 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use clap::Args;
+use json_parser::JsonPath;
+use rayon::prelude::*;
+
+use super::ParserArgs;
+
+#[derive(Args)]
+pub struct QueryArgs {
+    #[arg(help = "RFC 9535 JSONPath query such as '$.items[?@.price < 10].name'")]
+    path: JsonPath,
+
+    #[arg(required = true)]
+    files: Vec<PathBuf>,
+
+    #[arg(long, help = "Prints only the selected values, without their paths")]
+    values: bool,
+
+    #[arg(short, long, help = "Processes files sequentially using 1 thread")]
+    sequential: bool,
+
+    #[command(flatten)]
+    parser: ParserArgs,
+}
+
+pub fn run(args: QueryArgs) -> ExitCode {
+    let pool = super::thread_pool(args.sequential, args.files.len());
+
+    let results: Vec<Result<Vec<String>, String>> = pool.install(|| {
+        args.files
+            .par_iter()
+            .map(|file| query_file(file, &args))
+            .collect()
+    });
+
+    let show_file = args.files.len() > 1;
+    let mut failed = false;
+
+    for (file, result) in args.files.iter().zip(results) {
+        match result {
+            Ok(lines) => {
+                for line in lines {
+                    if show_file {
+                        println!("{}: {line}", file.display());
+                    } else {
+                        println!("{line}");
+                    }
+                }
+            }
+            Err(e) => {
+                eprintln!("Failed to query {}: {e}", file.display());
+                failed = true;
+            }
+        }
+    }
+
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+/// Returns one line for each selected node.
+fn query_file(file: &Path, args: &QueryArgs) -> Result<Vec<String>, String> {
+    let input = fs::read(file).map_err(|e| format!("Error reading file: {e}"))?;
+    let value = super::parse(&input, &file.display().to_string(), args.parser.options())?;
+
+    let lines = args
+        .path
+        .query(&value)
+        .into_iter()
+        .map(|node| {
+            if args.values {
+                node.value.to_string()
+            } else {
+                format!("{} = {}", node.path, node.value)
+            }
+        })
+        .collect();
+
+    Ok(lines)
+}
@@ -0,0 +1,211 @@
+use crate::lexer::{Lexer, LexerError, LexerErrorKind, NumberError, StringError};
+use crate::parser::{ParserError, ParserErrorKind};
+
+/// Longest stretch of a source line shown in a snippet, so that minified
+/// documents on a single line still render readably.
+const MAX_SNIPPET_WIDTH: usize = 100;
+
+/// A lexer or parser error enriched with everything needed to point at the
+/// offending input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub message: String,
+    pub line: usize,
+    pub col: usize,
+    /// Number of bytes to underline, starting at `col`.
+    pub len: usize,
+    pub hint: Option<String>,
+}
+
+impl Diagnostic {
+    pub fn from_lexer_error(error: &LexerError) -> Self {
+        let (col, len) = match &error.kind {
+            LexerErrorKind::InvalidLiteral(literal) => (
+                error.col.saturating_sub(literal.len()).max(1),
+                literal.len(),
+            ),
+            _ => (error.col, 1),
+        };
+
+        Self {
+            message: error.kind.to_string(),
+            line: error.line,
+            col,
+            len,
+            hint: lexer_hint(&error.kind),
+        }
+    }
+
+    pub fn from_parser_error(error: &ParserError, input: &[u8]) -> Self {
+        let len = input
+            .get(error.offset..)
+            .and_then(|rest| Lexer::new(rest).next_span())
+            .map_or(1, |span| span.len());
+
+        Self {
+            message: error.kind.to_string(),
+            line: error.line,
+            col: error.col,
+            len,
+            hint: parser_hint(&error.kind),
+        }
+    }
+
+    /// Renders the diagnostic against the input it was produced from, showing
+    /// the offending line with the bad token underlined.
+    pub fn render(&self, input: &[u8], source_name: &str) -> String {
+        let line = input
+            .split(|&b| b == b'\n')
+            .nth(self.line.saturating_sub(1))
+            .unwrap_or_default();
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+
+        let start = self.col.saturating_sub(1).min(line.len());
+        let mut window_start = start.saturating_sub(MAX_SNIPPET_WIDTH / 2);
+        while window_start > 0 && is_continuation(line[window_start]) {
+            window_start -= 1;
+        }
+        let mut window_end = (window_start + MAX_SNIPPET_WIDTH).min(line.len());
+        while window_end < line.len() && is_continuation(line[window_end]) {
+            window_end += 1;
+        }
+
+        let mut snippet = String::from_utf8_lossy(&line[window_start..window_end]).to_string();
+        let mut padding: String = String::from_utf8_lossy(&line[window_start..start])
+            .chars()
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+
+        if window_start > 0 {
+            snippet.insert_str(0, "...");
+            padding.insert_str(0, "   ");
+        }
+        if window_end < line.len() {
+            snippet.push_str("...");
+        }
+
+        let underlined = self.len.min(window_end.saturating_sub(start));
+        let carets = String::from_utf8_lossy(&line[start..start + underlined])
+            .chars()
+            .count()
+            .max(1);
+
+        let gutter = " ".repeat(self.line.to_string().len());
+        let mut out = format!(
+            "error: {}\n{gutter}--> {source_name}:{}:{}\n{gutter} |\n{} | {snippet}\n{gutter} | {padding}{}\n",
+            self.message,
+            self.line,
+            self.col,
+            self.line,
+            "^".repeat(carets),
+        );
+
+        if let Some(hint) = &self.hint {
+            out.push_str(&format!("{gutter} = help: {hint}\n"));
+        }
+
+        out
+    }
+}
+
+fn is_continuation(b: u8) -> bool {
+    b & 0xC0 == 0x80
+}
+
+fn lexer_hint(kind: &LexerErrorKind) -> Option<String> {
+    let hint = match kind {
+        LexerErrorKind::InvalidLiteral(literal) => match closest_literal(literal) {
+            Some(keyword) => format!("did you mean `{keyword}`?"),
+            None => "strings and keys must be wrapped in double quotes".to_string(),
+        },
+        LexerErrorKind::InvalidToken(b'\'') => "strings must use double quotes".to_string(),
+        LexerErrorKind::InvalidToken(_) => return None,
+        LexerErrorKind::InvalidString(error) => match error {
+            StringError::Unterminated => "add a closing `\"`".to_string(),
+            StringError::IncompleteEscape | StringError::InvalidEscape { .. } => {
+                "valid escapes are \\\" \\\\ \\/ \\b \\f \\n \\r \\t and \\uXXXX".to_string()
+            }
+            StringError::UnescapedControlCharacter { code: b'\n' } => {
+                "write line breaks inside strings as `\\n`".to_string()
+            }
+            StringError::UnescapedControlCharacter { code } => {
+                format!("write this character as `\\u{code:04X}`")
+            }
+            StringError::InvalidUnicodeEscape { .. } => {
+                "`\\u` must be followed by exactly four hexadecimal digits".to_string()
+            }
+            StringError::UnpairedSurrogate { .. } => {
+                "a high surrogate `\\uD800`-`\\uDBFF` must be followed by a low surrogate `\\uDC00`-`\\uDFFF`"
+                    .to_string()
+            }
+        },
+        LexerErrorKind::InvalidNumber(NumberError::LeadingZero) => {
+            "remove the leading zero".to_string()
+        }
+        LexerErrorKind::InvalidNumber(_) | LexerErrorKind::Eof => return None,
+    };
+
+    Some(hint)
+}
+
+/// Returns the json keyword that `literal` is most likely a misspelling
+/// of, ignoring case, such as `true` for `Tru` or `ture`.
+fn closest_literal(literal: &str) -> Option<&'static str> {
+    let lower = literal.to_ascii_lowercase();
+
+    ["true", "false", "null"]
+        .into_iter()
+        .map(|keyword| (edit_distance(&lower, keyword), keyword))
+        .filter(|&(distance, _)| distance <= 2 && distance < lower.len())
+        .min_by_key(|&(distance, _)| distance)
+        .map(|(_, keyword)| keyword)
+}
+
+/// Levenshtein distance, counting an adjacent transposition as one edit.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    let mut rows = vec![(0..=b.len()).collect::<Vec<_>>()];
+
+    for i in 1..=a.len() {
+        let mut row = vec![i; b.len() + 1];
+
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            row[j] = (rows[i - 1][j] + 1)
+                .min(row[j - 1] + 1)
+                .min(rows[i - 1][j - 1] + cost);
+
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                row[j] = row[j].min(rows[i - 2][j - 2] + 1);
+            }
+        }
+
+        rows.push(row);
+    }
+
+    rows[a.len()][b.len()]
+}
+
+fn parser_hint(kind: &ParserErrorKind) -> Option<String> {
+    let hint = match kind {
+        ParserErrorKind::TrailingComma => "remove this comma",
+        ParserErrorKind::MissingColon => "add `:` between the key and its value",
+        ParserErrorKind::InvalidKey => "wrap the key in double quotes",
+        ParserErrorKind::DuplicateKey { .. } => "rename or remove one of the duplicate keys",
+        ParserErrorKind::InvalidArray => "add `,` between array values",
+        ParserErrorKind::TrailingTokens { .. } => {
+            "remove everything after the first value, or wrap the values in an array"
+        }
+        ParserErrorKind::UnexpectedEof => "close every open object and array",
+        ParserErrorKind::EmptyDocument => "add a json value such as `{}`",
+        ParserErrorKind::DepthLimitExceeded { .. } => {
+            "flatten the document or raise the maximum nesting depth"
+        }
+        ParserErrorKind::InvalidValue { .. } | ParserErrorKind::NonUTF8String => return None,
+    };
+
+    Some(hint.to_string())
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,124 @@
+use super::*;
+use crate::parser::Parser;
+
+fn lexer_diagnostic(input: &str) -> Diagnostic {
+    let error = Lexer::new(input.as_bytes())
+        .lex()
+        .expect_err("expected lexer to error");
+
+    Diagnostic::from_lexer_error(&error)
+}
+
+fn parser_diagnostic(input: &str) -> Diagnostic {
+    let bytes = input.as_bytes();
+    let tokens = Lexer::new(bytes).lex().expect("Lexing failed");
+    let error = Parser::new(tokens, bytes)
+        .parse()
+        .expect_err("expected parser to error");
+
+    Diagnostic::from_parser_error(&error, bytes)
+}
+
+#[test]
+fn invalid_literal_underlines_literal() {
+    let input = "{\n  \"key\": True\n}";
+    let diagnostic = lexer_diagnostic(input);
+
+    assert_eq!(
+        (diagnostic.line, diagnostic.col, diagnostic.len),
+        (2, 10, 4)
+    );
+    assert_eq!(diagnostic.hint.as_deref(), Some("did you mean `true`?"));
+    assert_eq!(
+        diagnostic.render(input.as_bytes(), "test.json"),
+        "error: [invalid literal] True\n \
+         --> test.json:2:10\n  \
+         |\n\
+         2 |   \"key\": True\n  \
+         |          ^^^^\n  \
+         = help: did you mean `true`?\n"
+    );
+}
+
+#[test]
+fn near_miss_literals_suggest_the_keyword() {
+    let hint = |input: &str| lexer_diagnostic(input).hint;
+
+    for (input, keyword) in [
+        ("Tru", "true"),
+        ("ture", "true"),
+        ("[fals]", "false"),
+        ("{\"a\": flase}", "false"),
+        ("nul", "null"),
+        ("NULL", "null"),
+        ("nil", "null"),
+    ] {
+        assert_eq!(
+            hint(input),
+            Some(format!("did you mean `{keyword}`?")),
+            "{input}"
+        );
+    }
+
+    assert_eq!(
+        hint("[abc]").as_deref(),
+        Some("strings and keys must be wrapped in double quotes")
+    );
+    assert_eq!(
+        hint("{key: 1}").as_deref(),
+        Some("strings and keys must be wrapped in double quotes")
+    );
+}
+
+#[test]
+fn trailing_comma_underlines_comma() {
+    let input = "[1, 2,]";
+    let diagnostic = parser_diagnostic(input);
+
+    assert_eq!((diagnostic.line, diagnostic.col, diagnostic.len), (1, 6, 1));
+    assert_eq!(diagnostic.hint.as_deref(), Some("remove this comma"));
+    assert!(
+        diagnostic
+            .render(input.as_bytes(), "test.json")
+            .contains("1 | [1, 2,]\n  |      ^\n")
+    );
+}
+
+#[test]
+fn parser_error_underlines_whole_token() {
+    let input = "{\"key\": 1, \"key\": 2}";
+    let diagnostic = parser_diagnostic(input);
+
+    assert_eq!((diagnostic.col, diagnostic.len), (12, 5));
+    assert!(
+        diagnostic
+            .render(input.as_bytes(), "test.json")
+            .contains("  |            ^^^^^\n")
+    );
+}
+
+#[test]
+fn long_lines_are_truncated_around_the_error() {
+    let input = format!("[{}True]", "1,".repeat(500));
+    let diagnostic = lexer_diagnostic(&input);
+    let rendered = diagnostic.render(input.as_bytes(), "test.json");
+    let snippet = rendered.lines().nth(3).expect("missing snippet line");
+
+    assert!(snippet.starts_with("1 | ..."));
+    assert!(snippet.ends_with("1,True]"));
+    assert!(snippet.len() < 120);
+    assert!(rendered.contains("^^^^\n"));
+}
+
+#[test]
+fn unexpected_eof_points_past_the_end() {
+    let input = "[1,";
+    let diagnostic = parser_diagnostic(input);
+
+    assert_eq!((diagnostic.line, diagnostic.col, diagnostic.len), (1, 4, 1));
+    assert!(
+        diagnostic
+            .render(input.as_bytes(), "test.json")
+            .contains("1 | [1,\n  |    ^\n")
+    );
+}
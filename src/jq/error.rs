@@ -0,0 +1,12 @@
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum JqError {
+    /// The filter text is malformed, located by its 1-based column.
+    #[error("Error at col {col}: {message}")]
+    Syntax { message: String, col: usize },
+
+    /// Evaluating the filter failed, for example by indexing a number.
+    #[error("{0}")]
+    Runtime(String),
+}
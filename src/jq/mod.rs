@@ -0,0 +1,721 @@
+pub mod error;
+mod parse;
+
+pub use error::JqError;
+
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::mem;
+use std::str::FromStr;
+
+use crate::parser::{Array, Number, Object, Value};
+
+type Result<T> = std::result::Result<T, JqError>;
+
+/// An output of a filter, which borrows from the input whenever it is part
+/// of it, so that paths such as `.a.b.c` copy nothing until the very end.
+type Output<'v, 'a> = Cow<'v, Value<'a>>;
+
+/// A compiled filter in a small subset of the jq language, such as
+/// `.items[] | select(.price < 10) | {name, total: (.price * .count)}`.
+///
+/// Supports paths, iteration, slices, pipes, commas, array and object
+/// construction, arithmetic, comparisons, `and`/`or` and the builtins
+/// `select`, `map`, `keys`, `length`, `has`, `add`, `type`, `not` and
+/// `empty`. Variables, user defined functions and assignment are not.
+#[derive(Clone, Debug, PartialEq)]
+pub struct JqFilter {
+    filter: Filter,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Filter {
+    /// `.`
+    Identity,
+    /// `..`, every value in the input starting with the input itself.
+    Recurse,
+    Literal(Value<'static>),
+    /// `target[index]`, including `.name` and `."name"`.
+    Index(Box<Filter>, Box<Filter>),
+    /// `target[]`
+    Iterate(Box<Filter>),
+    Slice {
+        target: Box<Filter>,
+        from: Option<Box<Filter>>,
+        to: Option<Box<Filter>>,
+    },
+    /// `filter?`, which discards the error if `filter` fails.
+    Try(Box<Filter>),
+    /// `[filter]`, or `[]` without one.
+    Array(Option<Box<Filter>>),
+    /// `{key: value, ...}`
+    Object(Vec<(Filter, Filter)>),
+    Negate(Box<Filter>),
+    Pipe(Box<Filter>, Box<Filter>),
+    Comma(Box<Filter>, Box<Filter>),
+    Binary(BinaryOp, Box<Filter>, Box<Filter>),
+    And(Box<Filter>, Box<Filter>),
+    Or(Box<Filter>, Box<Filter>),
+    Call(Builtin, Vec<Filter>),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Builtin {
+    Empty,
+    Not,
+    Length,
+    Keys,
+    Add,
+    Type,
+    Select,
+    Map,
+    Has,
+}
+
+impl JqFilter {
+    /// Runs the filter on `input`, returning every value it produces.
+    pub fn apply<'a>(&self, input: &Value<'a>) -> Result<Vec<Value<'a>>> {
+        let mut out = Vec::new();
+        self.filter.run(input, &mut out)?;
+
+        Ok(out.into_iter().map(Cow::into_owned).collect())
+    }
+}
+
+impl FromStr for JqFilter {
+    type Err = JqError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        parse::parse(s).map(|filter| Self { filter })
+    }
+}
+
+impl Filter {
+    fn string(s: String) -> Self {
+        Filter::Literal(Value::String(Cow::Owned(s)))
+    }
+
+    fn eval<'v, 'a>(&self, input: &'v Value<'a>) -> Result<Vec<Output<'v, 'a>>> {
+        let mut out = Vec::new();
+        self.run(input, &mut out)?;
+
+        Ok(out)
+    }
+
+    /// Like [`Filter::run`], for an input that another filter produced.
+    /// Outputs that borrow from an input it produced itself are copied.
+    fn run_on<'v, 'a>(&self, input: Output<'v, 'a>, out: &mut Vec<Output<'v, 'a>>) -> Result<()> {
+        match input {
+            Cow::Borrowed(input) => self.run(input, out),
+            Cow::Owned(input) => {
+                let outputs = self.eval(&input)?;
+                out.extend(outputs.into_iter().map(|v| Cow::Owned(v.into_owned())));
+
+                Ok(())
+            }
+        }
+    }
+
+    /// Appends the outputs of the filter for `input` to `out`.
+    fn run<'v, 'a>(&self, input: &'v Value<'a>, out: &mut Vec<Output<'v, 'a>>) -> Result<()> {
+        match self {
+            Filter::Identity => out.push(Cow::Borrowed(input)),
+            Filter::Recurse => {
+                let mut stack = vec![input];
+
+                while let Some(value) = stack.pop() {
+                    out.push(Cow::Borrowed(value));
+
+                    match value {
+                        Value::Array(array) => stack.extend(array.iter().rev()),
+                        Value::Object(object) => {
+                            stack.extend(object.iter().rev().map(|(_, value)| value))
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            Filter::Literal(value) => out.push(Cow::Owned(value.clone())),
+            Filter::Index(target, index) => {
+                let indices = index.eval(input)?;
+
+                for target in target.eval(input)? {
+                    for index in &indices {
+                        let value = match &target {
+                            Cow::Borrowed(target) => self::index(target, index)?.map(Cow::Borrowed),
+                            Cow::Owned(target) => {
+                                self::index(target, index)?.cloned().map(Cow::Owned)
+                            }
+                        };
+                        out.push(value.unwrap_or(Cow::Owned(Value::Null)));
+                    }
+                }
+            }
+            Filter::Iterate(target) => {
+                for target in target.eval(input)? {
+                    match target {
+                        Cow::Borrowed(Value::Array(array)) => {
+                            out.extend(array.iter().map(Cow::Borrowed));
+                        }
+                        Cow::Borrowed(Value::Object(object)) => {
+                            out.extend(object.values().map(Cow::Borrowed));
+                        }
+                        Cow::Owned(Value::Array(array)) => {
+                            out.extend(array.into_vec().into_iter().map(Cow::Owned));
+                        }
+                        Cow::Owned(Value::Object(object)) => {
+                            out.extend(object.into_iter().map(|(_, value)| Cow::Owned(value)));
+                        }
+                        other => {
+                            return Err(runtime(format!("Cannot iterate over {}", other.kind())));
+                        }
+                    }
+                }
+            }
+            Filter::Slice { target, from, to } => {
+                let bound = |filter: &Option<Box<Filter>>| match filter {
+                    Some(filter) => filter.eval(input),
+                    None => Ok(vec![Cow::Owned(Value::Null)]),
+                };
+                let (froms, tos) = (bound(from)?, bound(to)?);
+
+                for target in target.eval(input)? {
+                    for to in &tos {
+                        for from in &froms {
+                            out.push(Cow::Owned(slice(&target, from, to)?));
+                        }
+                    }
+                }
+            }
+            Filter::Try(filter) => {
+                // Like jq, keep whatever was produced before the error.
+                let _ = filter.run(input, out);
+            }
+            Filter::Array(None) => out.push(Cow::Owned(Value::Array(Array::new()))),
+            Filter::Array(Some(elements)) => {
+                let elements: Vec<_> = elements
+                    .eval(input)?
+                    .into_iter()
+                    .map(Cow::into_owned)
+                    .collect();
+                out.push(Cow::Owned(Value::Array(elements.into())));
+            }
+            Filter::Object(members) => {
+                let mut objects = vec![Object::new()];
+
+                for (key, value) in members {
+                    let keys = key.eval(input)?;
+                    let values = value.eval(input)?;
+                    let mut next = Vec::with_capacity(objects.len() * keys.len() * values.len());
+
+                    for object in &objects {
+                        for key in &keys {
+                            let Value::String(key) = &**key else {
+                                return Err(runtime(format!(
+                                    "Object keys must be strings, found {}",
+                                    key.kind()
+                                )));
+                            };
+
+                            for value in &values {
+                                let mut object = object.clone();
+                                object.insert(key.clone(), Value::clone(value));
+                                next.push(object);
+                            }
+                        }
+                    }
+
+                    objects = next;
+                }
+
+                out.extend(
+                    objects
+                        .into_iter()
+                        .map(|object| Cow::Owned(Value::Object(object))),
+                );
+            }
+            Filter::Negate(operand) => {
+                for value in operand.eval(input)? {
+                    let Value::Number(n) = &*value else {
+                        return Err(runtime(format!(
+                            "{} ({value}) cannot be negated",
+                            value.kind()
+                        )));
+                    };
+
+                    let negated = match n.as_str().strip_prefix('-') {
+                        Some(abs) => abs.to_string(),
+                        None => format!("-{n}"),
+                    };
+                    out.push(Cow::Owned(Value::Number(Number::from_raw(negated))));
+                }
+            }
+            Filter::Pipe(left, right) => {
+                for value in left.eval(input)? {
+                    right.run_on(value, out)?;
+                }
+            }
+            Filter::Comma(left, right) => {
+                left.run(input, out)?;
+                right.run(input, out)?;
+            }
+            Filter::Binary(op, left, right) => {
+                let lefts = left.eval(input)?;
+
+                // jq varies the left operand fastest.
+                for r in right.eval(input)? {
+                    for l in &lefts {
+                        out.push(Cow::Owned(op.apply(l, &r)?));
+                    }
+                }
+            }
+            Filter::And(left, right) | Filter::Or(left, right) => {
+                let is_and = matches!(self, Filter::And(..));
+
+                for l in left.eval(input)? {
+                    if is_truthy(&l) != is_and {
+                        out.push(Cow::Owned(Value::Boolean(!is_and)));
+                        continue;
+                    }
+
+                    for r in right.eval(input)? {
+                        out.push(Cow::Owned(Value::Boolean(is_truthy(&r))));
+                    }
+                }
+            }
+            Filter::Call(builtin, args) => builtin.run(args, input, out)?,
+        }
+
+        Ok(())
+    }
+}
+
+impl Builtin {
+    fn run<'v, 'a>(
+        &self,
+        args: &[Filter],
+        input: &'v Value<'a>,
+        out: &mut Vec<Output<'v, 'a>>,
+    ) -> Result<()> {
+        match self {
+            Builtin::Empty => {}
+            Builtin::Not => out.push(Cow::Owned(Value::Boolean(!is_truthy(input)))),
+            Builtin::Length => out.push(Cow::Owned(length(input)?)),
+            Builtin::Keys => {
+                let keys: Vec<_> = match input {
+                    Value::Object(object) => {
+                        let mut keys: Vec<_> = object.keys().collect();
+                        keys.sort_unstable();
+                        keys.dedup();
+
+                        keys.into_iter()
+                            .map(|key| Value::String(Cow::Owned(key.to_string())))
+                            .collect()
+                    }
+                    Value::Array(array) => (0..array.len())
+                        .map(|i| Value::Number(Number::from(i)))
+                        .collect(),
+                    other => return Err(runtime(format!("{} has no keys", other.kind()))),
+                };
+
+                out.push(Cow::Owned(Value::Array(keys.into())));
+            }
+            Builtin::Add => {
+                let values: Vec<&Value<'a>> = match input {
+                    Value::Array(array) => array.iter().collect(),
+                    Value::Object(object) => object.values().collect(),
+                    Value::Null => Vec::new(),
+                    other => return Err(runtime(format!("Cannot iterate over {}", other.kind()))),
+                };
+
+                let sum = values
+                    .into_iter()
+                    .try_fold(Value::Null, |sum, value| BinaryOp::Add.apply(&sum, value))?;
+                out.push(Cow::Owned(sum));
+            }
+            Builtin::Type => out.push(Cow::Owned(Value::String(Cow::Owned(
+                input.kind().to_string(),
+            )))),
+            Builtin::Select => {
+                for condition in args[0].eval(input)? {
+                    if is_truthy(&condition) {
+                        out.push(Cow::Borrowed(input));
+                    }
+                }
+            }
+            Builtin::Map => {
+                let mut mapped = Vec::new();
+
+                for value in Filter::Iterate(Box::new(Filter::Identity)).eval(input)? {
+                    args[0].run_on(value, &mut mapped)?;
+                }
+
+                let mapped: Vec<_> = mapped.into_iter().map(Cow::into_owned).collect();
+                out.push(Cow::Owned(Value::Array(mapped.into())));
+            }
+            Builtin::Has => {
+                for key in args[0].eval(input)? {
+                    let has = match (input, &*key) {
+                        (Value::Object(object), Value::String(key)) => object.contains_key(key),
+                        (Value::Array(array), Value::Number(n)) => {
+                            let i = n.as_f64_lossy();
+                            i >= 0.0 && i < array.len() as f64
+                        }
+                        _ => {
+                            return Err(runtime(format!(
+                                "Cannot check whether {} has a {} key",
+                                input.kind(),
+                                key.kind()
+                            )));
+                        }
+                    };
+
+                    out.push(Cow::Owned(Value::Boolean(has)));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl BinaryOp {
+    fn is_comparison(self) -> bool {
+        matches!(
+            self,
+            BinaryOp::Eq | BinaryOp::Ne | BinaryOp::Lt | BinaryOp::Le | BinaryOp::Gt | BinaryOp::Ge
+        )
+    }
+
+    fn apply<'a>(self, l: &Value<'a>, r: &Value<'a>) -> Result<Value<'a>> {
+        let ordering = || compare(l, r);
+
+        let value = match (self, l, r) {
+            (BinaryOp::Eq, ..) => Value::Boolean(ordering().is_eq()),
+            (BinaryOp::Ne, ..) => Value::Boolean(ordering().is_ne()),
+            (BinaryOp::Lt, ..) => Value::Boolean(ordering().is_lt()),
+            (BinaryOp::Le, ..) => Value::Boolean(ordering().is_le()),
+            (BinaryOp::Gt, ..) => Value::Boolean(ordering().is_gt()),
+            (BinaryOp::Ge, ..) => Value::Boolean(ordering().is_ge()),
+            (_, Value::Number(a), Value::Number(b)) => Value::Number(self.arithmetic(a, b)?),
+            (BinaryOp::Add, Value::Null, other) | (BinaryOp::Add, other, Value::Null) => {
+                other.clone()
+            }
+            (BinaryOp::Add, Value::String(a), Value::String(b)) => {
+                Value::String(Cow::Owned(format!("{a}{b}")))
+            }
+            (BinaryOp::Add, Value::Array(a), Value::Array(b)) => {
+                Value::Array(a.iter().chain(b.iter()).cloned().collect())
+            }
+            (BinaryOp::Add, Value::Object(a), Value::Object(b)) => {
+                let mut merged = a.clone();
+
+                for (key, value) in b {
+                    merged.insert(key.to_string(), value.clone());
+                }
+
+                Value::Object(merged)
+            }
+            (BinaryOp::Sub, Value::Array(a), Value::Array(b)) => Value::Array(
+                a.iter()
+                    .filter(|x| !b.iter().any(|y| compare(x, y).is_eq()))
+                    .cloned()
+                    .collect(),
+            ),
+            (BinaryOp::Mul, Value::Object(a), Value::Object(b)) => {
+                Value::Object(deep_merge(a.clone(), b))
+            }
+            (BinaryOp::Div, Value::String(a), Value::String(b)) => {
+                let parts: Vec<_> = if a.is_empty() {
+                    Vec::new()
+                } else if b.is_empty() {
+                    a.chars().map(String::from).collect()
+                } else {
+                    a.split(b.as_ref()).map(str::to_string).collect()
+                };
+
+                Value::Array(
+                    parts
+                        .into_iter()
+                        .map(|part| Value::String(Cow::Owned(part)))
+                        .collect(),
+                )
+            }
+            _ => {
+                let verb = match self {
+                    BinaryOp::Add => "added",
+                    BinaryOp::Sub => "subtracted",
+                    BinaryOp::Mul => "multiplied",
+                    _ => "divided",
+                };
+
+                return Err(runtime(format!(
+                    "{} ({l}) and {} ({r}) cannot be {verb}",
+                    l.kind(),
+                    r.kind()
+                )));
+            }
+        };
+
+        Ok(value)
+    }
+
+    /// Computes with exact integers when both operands are integers and the
+    /// result fits, and with 64-bit floats otherwise.
+    fn arithmetic(self, a: &Number<'_>, b: &Number<'_>) -> Result<Number<'static>> {
+        let divided_by_zero = || {
+            runtime(format!(
+                "{a} and {b} cannot be divided because the divisor is zero"
+            ))
+        };
+
+        if self == BinaryOp::Rem {
+            // jq truncates both operands of `%` to integers.
+            let (x, y) = (a.as_f64_lossy() as i64, b.as_f64_lossy() as i64);
+
+            if y == 0 {
+                return Err(divided_by_zero());
+            }
+
+            return Ok(Number::from(x.checked_rem(y).unwrap_or(0)));
+        }
+
+        if let (Ok(x), Ok(y)) = (a.as_i64(), b.as_i64()) {
+            let exact = match self {
+                BinaryOp::Add => x.checked_add(y),
+                BinaryOp::Sub => x.checked_sub(y),
+                BinaryOp::Mul => x.checked_mul(y),
+                BinaryOp::Div if y != 0 && x.checked_rem(y) == Some(0) => x.checked_div(y),
+                _ => None,
+            };
+
+            if let Some(n) = exact {
+                return Ok(Number::from(n));
+            }
+        }
+
+        let (x, y) = (a.as_f64_lossy(), b.as_f64_lossy());
+
+        let f = match self {
+            BinaryOp::Add => x + y,
+            BinaryOp::Sub => x - y,
+            BinaryOp::Mul => x * y,
+            BinaryOp::Div if y == 0.0 => return Err(divided_by_zero()),
+            BinaryOp::Div => x / y,
+            _ => unreachable!("only arithmetic operators reach here"),
+        };
+
+        // Print whole results without a fraction, as jq does.
+        if f.fract() == 0.0 && f.abs() < 2f64.powi(53) {
+            return Ok(Number::from(f as i64));
+        }
+
+        Number::from_f64(f).ok_or_else(|| runtime(format!("{a} and {b} overflowed to {f}")))
+    }
+}
+
+/// Looks up `index` in `target`, giving `None` where jq gives `null`.
+fn index<'v, 'a>(target: &'v Value<'a>, index: &Value<'a>) -> Result<Option<&'v Value<'a>>> {
+    match (target, index) {
+        (Value::Object(object), Value::String(key)) => Ok(object.get(key)),
+        (Value::Array(array), Value::Number(n)) => {
+            let i = n.as_f64_lossy().floor();
+            let i = if i < 0.0 { i + array.len() as f64 } else { i };
+
+            Ok((i >= 0.0 && i < array.len() as f64).then(|| &array[i as usize]))
+        }
+        (Value::Null, Value::String(_) | Value::Number(_)) => Ok(None),
+        _ => Err(runtime(format!(
+            "Cannot index {} with {}",
+            target.kind(),
+            match index {
+                Value::String(_) => index.to_string(),
+                other => other.kind().to_string(),
+            }
+        ))),
+    }
+}
+
+/// Slices an array or string by element or character, counting negative
+/// bounds from the end and clamping both bounds to the length.
+fn slice<'a>(target: &Value<'a>, from: &Value<'a>, to: &Value<'a>) -> Result<Value<'a>> {
+    let len = match target {
+        Value::Null => return Ok(Value::Null),
+        Value::Array(array) => array.len(),
+        Value::String(s) => s.chars().count(),
+        other => return Err(runtime(format!("Cannot slice {}", other.kind()))),
+    };
+
+    let bound = |value: &Value<'_>, default: usize, round: fn(f64) -> f64| match value {
+        Value::Null => Ok(default),
+        Value::Number(n) => {
+            let i = round(n.as_f64_lossy());
+            let i = if i < 0.0 { i + len as f64 } else { i };
+            Ok(i.clamp(0.0, len as f64) as usize)
+        }
+        other => Err(runtime(format!(
+            "Slice bounds must be numbers, found {}",
+            other.kind()
+        ))),
+    };
+
+    let from = bound(from, 0, f64::floor)?;
+    let to = bound(to, len, f64::ceil)?.max(from);
+
+    Ok(match target {
+        Value::Array(array) => Value::Array(array[from..to].to_vec().into()),
+        Value::String(s) => {
+            Value::String(Cow::Owned(s.chars().skip(from).take(to - from).collect()))
+        }
+        _ => unreachable!("other kinds returned above"),
+    })
+}
+
+fn length<'a>(value: &Value<'a>) -> Result<Value<'a>> {
+    let n = match value {
+        Value::Null => 0,
+        Value::String(s) => s.chars().count(),
+        Value::Array(array) => array.len(),
+        Value::Object(object) => object.len(),
+        Value::Number(n) => {
+            let abs = n.as_str().strip_prefix('-').unwrap_or(n.as_str());
+            return Ok(Value::Number(Number::from_raw(abs.to_string())));
+        }
+        Value::Boolean(_) => return Err(runtime("boolean has no length".to_string())),
+    };
+
+    Ok(Value::Number(Number::from(n)))
+}
+
+/// Merges `right` into `left`, combining objects present on both sides
+/// instead of replacing them.
+fn deep_merge<'a>(left: Object<'a>, right: &Object<'a>) -> Object<'a> {
+    // Each object being merged, with the members of its counterpart still to
+    // merge into it and the key it goes back under in its parent.
+    let mut stack = vec![(left, right.iter(), String::new())];
+
+    loop {
+        let (left, members, _) = stack.last_mut().expect("the root is merged last");
+
+        let nested = match members.next() {
+            Some((key, Value::Object(r))) => match left.get_mut(key) {
+                Some(Value::Object(l)) => Some((mem::take(l), r.iter(), key.to_string())),
+                _ => {
+                    left.insert(key.to_string(), Value::Object(r.clone()));
+                    None
+                }
+            },
+            Some((key, value)) => {
+                left.insert(key.to_string(), value.clone());
+                None
+            }
+            None => {
+                let (merged, _, key) = stack.pop().expect("the frame was just inspected");
+
+                match stack.last_mut() {
+                    Some((parent, _, _)) => parent.insert(key, Value::Object(merged)),
+                    None => return merged,
+                };
+
+                None
+            }
+        };
+
+        stack.extend(nested);
+    }
+}
+
+/// Orders values the way jq sorts them: null, false, true, numbers,
+/// strings, arrays and then objects, which compare by their sorted keys
+/// before their values.
+fn compare(a: &Value<'_>, b: &Value<'_>) -> Ordering {
+    /// Comparisons still to make, popped from the back. Containers are
+    /// decided by their members first and then by a fallback ordering.
+    enum Step<'b, 'a> {
+        Compare(&'b Value<'a>, &'b Value<'a>),
+        Decided(Ordering),
+    }
+
+    let rank = |value: &Value<'_>| match value {
+        Value::Null => 0,
+        Value::Boolean(false) => 1,
+        Value::Boolean(true) => 2,
+        Value::Number(_) => 3,
+        Value::String(_) => 4,
+        Value::Array(_) => 5,
+        Value::Object(_) => 6,
+    };
+    let sorted_keys = |object: &Object<'_>| {
+        let mut keys: Vec<String> = object.keys().map(str::to_string).collect();
+        keys.sort_unstable();
+        keys
+    };
+
+    let mut stack = vec![Step::Compare(a, b)];
+
+    while let Some(step) = stack.pop() {
+        let ordering =
+            match step {
+                Step::Decided(ordering) => ordering,
+                Step::Compare(Value::Number(a), Value::Number(b)) => a.cmp(b),
+                Step::Compare(Value::String(a), Value::String(b)) => a.cmp(b),
+                Step::Compare(Value::Array(a), Value::Array(b)) => {
+                    stack.push(Step::Decided(a.len().cmp(&b.len())));
+                    stack.extend(
+                        a.iter()
+                            .zip(b.iter())
+                            .rev()
+                            .map(|(a, b)| Step::Compare(a, b)),
+                    );
+                    Ordering::Equal
+                }
+                Step::Compare(Value::Object(a), Value::Object(b)) => {
+                    let (keys_a, keys_b) = (sorted_keys(a), sorted_keys(b));
+
+                    if keys_a == keys_b {
+                        stack.extend(keys_a.iter().rev().map(|key| {
+                            match (a.get(key), b.get(key)) {
+                                (Some(a), Some(b)) => Step::Compare(a, b),
+                                _ => unreachable!("both objects have the same keys"),
+                            }
+                        }));
+                    }
+
+                    keys_a.cmp(&keys_b)
+                }
+                Step::Compare(a, b) => rank(a).cmp(&rank(b)),
+            };
+
+        if ordering.is_ne() {
+            return ordering;
+        }
+    }
+
+    Ordering::Equal
+}
+
+fn is_truthy(value: &Value<'_>) -> bool {
+    !matches!(value, Value::Null | Value::Boolean(false))
+}
+
+fn runtime(message: String) -> JqError {
+    JqError::Runtime(message)
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,555 @@
+use super::error::JqError;
+use super::{BinaryOp, Builtin, Filter};
+use crate::lexer::Lexer;
+use crate::parser::{Number, Parser, Value};
+
+type Result<T> = std::result::Result<T, JqError>;
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Dot,
+    DotDot,
+    /// `.name`, a field access written without brackets.
+    Field(String),
+    Ident(String),
+    String(String),
+    Number(Number<'static>),
+    Lparen,
+    Rparen,
+    Lsquare,
+    Rsquare,
+    Lcurl,
+    Rcurl,
+    Pipe,
+    Comma,
+    Colon,
+    Semicolon,
+    Question,
+    Op(BinaryOp),
+}
+
+/// Parses a complete filter such as `.items[] | select(.active) | {id}`.
+pub(super) fn parse(filter: &str) -> Result<Filter> {
+    let tokens = tokenize(filter)?;
+    let mut parser = FilterParser {
+        tokens,
+        pos: 0,
+        end_col: filter.chars().count() + 1,
+    };
+
+    let filter = parser.parse_pipe()?;
+
+    if parser.pos < parser.tokens.len() {
+        return Err(parser.unexpected("'|' or the end of the filter"));
+    }
+
+    Ok(filter)
+}
+
+/// Splits the filter into tokens, each paired with its 1-based column.
+fn tokenize(filter: &str) -> Result<Vec<(Token, usize)>> {
+    let chars: Vec<(usize, char)> = filter.char_indices().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    let syntax = |message: String, i: usize| JqError::Syntax {
+        message,
+        col: i + 1,
+    };
+    let is_ident_start = |c: char| c == '_' || c.is_ascii_alphabetic();
+    let is_ident = |c: char| c == '_' || c.is_ascii_alphanumeric();
+
+    while i < chars.len() {
+        let (offset, c) = chars[i];
+        let col = i + 1;
+        let next = chars.get(i + 1).map(|&(_, c)| c);
+
+        let (token, len) =
+            match c {
+                ' ' | '\t' | '\n' | '\r' => {
+                    i += 1;
+                    continue;
+                }
+                '#' => {
+                    while i < chars.len() && chars[i].1 != '\n' {
+                        i += 1;
+                    }
+                    continue;
+                }
+                '.' if next == Some('.') => (Token::DotDot, 2),
+                '.' if next.is_some_and(is_ident_start) => {
+                    let len = chars[i + 1..]
+                        .iter()
+                        .take_while(|&&(_, c)| is_ident(c))
+                        .count();
+                    let name = chars[i + 1..i + 1 + len].iter().map(|&(_, c)| c).collect();
+                    (Token::Field(name), len + 1)
+                }
+                '.' => (Token::Dot, 1),
+                '"' => {
+                    let mut end = i + 1;
+                    let mut escaped = false;
+
+                    while end < chars.len() && (escaped || chars[end].1 != '"') {
+                        escaped = !escaped && chars[end].1 == '\\';
+                        end += 1;
+                    }
+
+                    if end == chars.len() {
+                        return Err(syntax("Unterminated string literal".to_string(), i));
+                    }
+
+                    let literal = &filter[offset..chars[end].0 + 1];
+                    (
+                        Token::String(parse_string(literal).ok_or_else(|| {
+                            syntax(format!("Invalid string literal {literal}"), i)
+                        })?),
+                        end + 1 - i,
+                    )
+                }
+                '0'..='9' => {
+                    let len = chars[i..]
+                        .iter()
+                        .take_while(|&&(_, c)| c.is_ascii_digit() || ".eE".contains(c))
+                        .count();
+                    // Allow a sign directly after an exponent marker.
+                    let mut len = len;
+                    while matches!(chars.get(i + len - 1), Some((_, 'e' | 'E')))
+                        && matches!(chars.get(i + len), Some((_, '+' | '-')))
+                    {
+                        len += 1 + chars[i + len + 1..]
+                            .iter()
+                            .take_while(|&&(_, c)| c.is_ascii_digit())
+                            .count();
+                    }
+
+                    let end = chars.get(i + len).map_or(filter.len(), |&(o, _)| o);
+                    let text = &filter[offset..end];
+                    let number = Number::parse(text)
+                        .ok_or_else(|| syntax(format!("Invalid number literal {text}"), i))?;
+                    (Token::Number(number.into_owned()), len)
+                }
+                c if is_ident_start(c) => {
+                    let len = chars[i..].iter().take_while(|&&(_, c)| is_ident(c)).count();
+                    let name = chars[i..i + len].iter().map(|&(_, c)| c).collect();
+                    (Token::Ident(name), len)
+                }
+                '(' => (Token::Lparen, 1),
+                ')' => (Token::Rparen, 1),
+                '[' => (Token::Lsquare, 1),
+                ']' => (Token::Rsquare, 1),
+                '{' => (Token::Lcurl, 1),
+                '}' => (Token::Rcurl, 1),
+                '|' => (Token::Pipe, 1),
+                ',' => (Token::Comma, 1),
+                ':' => (Token::Colon, 1),
+                ';' => (Token::Semicolon, 1),
+                '?' => (Token::Question, 1),
+                '=' if next == Some('=') => (Token::Op(BinaryOp::Eq), 2),
+                '!' if next == Some('=') => (Token::Op(BinaryOp::Ne), 2),
+                '<' if next == Some('=') => (Token::Op(BinaryOp::Le), 2),
+                '>' if next == Some('=') => (Token::Op(BinaryOp::Ge), 2),
+                '<' => (Token::Op(BinaryOp::Lt), 1),
+                '>' => (Token::Op(BinaryOp::Gt), 1),
+                '+' => (Token::Op(BinaryOp::Add), 1),
+                '-' => (Token::Op(BinaryOp::Sub), 1),
+                '*' => (Token::Op(BinaryOp::Mul), 1),
+                '/' => (Token::Op(BinaryOp::Div), 1),
+                '%' => (Token::Op(BinaryOp::Rem), 1),
+                c => return Err(syntax(format!("Unexpected character {c:?}"), i)),
+            };
+
+        tokens.push((token, col));
+        i += len;
+    }
+
+    Ok(tokens)
+}
+
+/// Decodes a double quoted string literal, which uses the same escapes as
+/// json strings.
+fn parse_string(literal: &str) -> Option<String> {
+    let tokens = Lexer::new(literal.as_bytes()).lex().ok()?;
+
+    match Parser::new(tokens, literal.as_bytes()).parse().ok()? {
+        Value::String(s) => Some(s.into_owned()),
+        _ => None,
+    }
+}
+
+struct FilterParser {
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+    /// Column reported for errors at the end of the filter.
+    end_col: usize,
+}
+
+impl FilterParser {
+    /// `a | b`, which binds loosest and groups to the right.
+    fn parse_pipe(&mut self) -> Result<Filter> {
+        let left = self.parse_comma()?;
+
+        if self.eat(&Token::Pipe) {
+            let right = self.parse_pipe()?;
+            return Ok(Filter::Pipe(Box::new(left), Box::new(right)));
+        }
+
+        Ok(left)
+    }
+
+    fn parse_comma(&mut self) -> Result<Filter> {
+        let mut left = self.parse_or()?;
+
+        while self.eat(&Token::Comma) {
+            let right = self.parse_or()?;
+            left = Filter::Comma(Box::new(left), Box::new(right));
+        }
+
+        Ok(left)
+    }
+
+    fn parse_or(&mut self) -> Result<Filter> {
+        let mut left = self.parse_and()?;
+
+        while self.eat_keyword("or") {
+            let right = self.parse_and()?;
+            left = Filter::Or(Box::new(left), Box::new(right));
+        }
+
+        Ok(left)
+    }
+
+    fn parse_and(&mut self) -> Result<Filter> {
+        let mut left = self.parse_comparison()?;
+
+        while self.eat_keyword("and") {
+            let right = self.parse_comparison()?;
+            left = Filter::And(Box::new(left), Box::new(right));
+        }
+
+        Ok(left)
+    }
+
+    /// Comparisons do not chain, so `a < b < c` is rejected.
+    fn parse_comparison(&mut self) -> Result<Filter> {
+        let left = self.parse_binary(&[BinaryOp::Add, BinaryOp::Sub], Self::parse_product)?;
+
+        let Some(Token::Op(op)) = self.peek() else {
+            return Ok(left);
+        };
+        let op = *op;
+
+        if !op.is_comparison() {
+            return Ok(left);
+        }
+
+        self.pos += 1;
+        let right = self.parse_binary(&[BinaryOp::Add, BinaryOp::Sub], Self::parse_product)?;
+
+        if let Some(Token::Op(op)) = self.peek()
+            && op.is_comparison()
+        {
+            return Err(self.unexpected("an operand, comparisons cannot be chained"));
+        }
+
+        Ok(Filter::Binary(op, Box::new(left), Box::new(right)))
+    }
+
+    fn parse_product(&mut self) -> Result<Filter> {
+        self.parse_binary(
+            &[BinaryOp::Mul, BinaryOp::Div, BinaryOp::Rem],
+            Self::parse_unary,
+        )
+    }
+
+    /// Parses left associative operators from `ops` between operands.
+    fn parse_binary(
+        &mut self,
+        ops: &[BinaryOp],
+        operand: fn(&mut Self) -> Result<Filter>,
+    ) -> Result<Filter> {
+        let mut left = operand(self)?;
+
+        while let Some(Token::Op(op)) = self.peek()
+            && ops.contains(op)
+        {
+            let op = *op;
+            self.pos += 1;
+            let right = operand(self)?;
+            left = Filter::Binary(op, Box::new(left), Box::new(right));
+        }
+
+        Ok(left)
+    }
+
+    fn parse_unary(&mut self) -> Result<Filter> {
+        if self.eat(&Token::Op(BinaryOp::Sub)) {
+            return Ok(Filter::Negate(Box::new(self.parse_unary()?)));
+        }
+
+        self.parse_postfix()
+    }
+
+    /// Parses a term followed by any field accesses, brackets and `?`.
+    fn parse_postfix(&mut self) -> Result<Filter> {
+        let mut filter = self.parse_term()?;
+
+        loop {
+            filter = match self.peek() {
+                Some(Token::Field(name)) => {
+                    let name = name.clone();
+                    self.pos += 1;
+                    Filter::Index(Box::new(filter), Box::new(Filter::string(name)))
+                }
+                Some(Token::Dot) if matches!(self.peek_at(1), Some(Token::String(_))) => {
+                    self.pos += 1;
+                    let Some(Token::String(name)) = self.next() else {
+                        unreachable!("checked above")
+                    };
+                    Filter::Index(Box::new(filter), Box::new(Filter::string(name)))
+                }
+                Some(Token::Dot) if self.peek_at(1) == Some(&Token::Lsquare) => {
+                    self.pos += 1;
+                    self.parse_brackets(filter)?
+                }
+                Some(Token::Lsquare) => self.parse_brackets(filter)?,
+                Some(Token::Question) => {
+                    self.pos += 1;
+                    Filter::Try(Box::new(filter))
+                }
+                _ => return Ok(filter),
+            };
+        }
+    }
+
+    /// Parses `[]`, `[index]` or `[from:to]` applied to `target`.
+    fn parse_brackets(&mut self, target: Filter) -> Result<Filter> {
+        self.expect(&Token::Lsquare, "'['")?;
+        let target = Box::new(target);
+
+        if self.eat(&Token::Rsquare) {
+            return Ok(Filter::Iterate(target));
+        }
+
+        let from = if self.peek() == Some(&Token::Colon) {
+            None
+        } else {
+            Some(Box::new(self.parse_pipe()?))
+        };
+
+        if self.eat(&Token::Colon) {
+            let to = if self.peek() == Some(&Token::Rsquare) {
+                None
+            } else {
+                Some(Box::new(self.parse_pipe()?))
+            };
+
+            self.expect(&Token::Rsquare, "']'")?;
+            return Ok(Filter::Slice { target, from, to });
+        }
+
+        self.expect(&Token::Rsquare, "']' or ':'")?;
+        let index = from.expect("an index is parsed unless a colon follows");
+
+        Ok(Filter::Index(target, index))
+    }
+
+    fn parse_term(&mut self) -> Result<Filter> {
+        let col = self.col();
+
+        match self.next() {
+            Some(Token::Dot) => match self.peek() {
+                Some(Token::String(_)) => {
+                    let Some(Token::String(name)) = self.next() else {
+                        unreachable!("checked above")
+                    };
+                    Ok(Filter::Index(
+                        Box::new(Filter::Identity),
+                        Box::new(Filter::string(name)),
+                    ))
+                }
+                Some(Token::Lsquare) => self.parse_brackets(Filter::Identity),
+                _ => Ok(Filter::Identity),
+            },
+            Some(Token::DotDot) => Ok(Filter::Recurse),
+            Some(Token::Field(name)) => Ok(Filter::Index(
+                Box::new(Filter::Identity),
+                Box::new(Filter::string(name)),
+            )),
+            Some(Token::String(s)) => Ok(Filter::string(s)),
+            Some(Token::Number(number)) => Ok(Filter::Literal(Value::Number(number))),
+            Some(Token::Lparen) => {
+                let filter = self.parse_pipe()?;
+                self.expect(&Token::Rparen, "')'")?;
+                Ok(filter)
+            }
+            Some(Token::Lsquare) => {
+                if self.eat(&Token::Rsquare) {
+                    return Ok(Filter::Array(None));
+                }
+
+                let elements = self.parse_pipe()?;
+                self.expect(&Token::Rsquare, "']'")?;
+                Ok(Filter::Array(Some(Box::new(elements))))
+            }
+            Some(Token::Lcurl) => self.parse_object(),
+            Some(Token::Ident(name)) => self.parse_call(name, col),
+            _ => {
+                self.pos = self.pos.saturating_sub(1);
+                Err(self.unexpected("a filter"))
+            }
+        }
+    }
+
+    /// Parses the members of `{...}` after the opening brace. A bare name
+    /// such as `{id}` is short for `{id: .id}`.
+    fn parse_object(&mut self) -> Result<Filter> {
+        let mut members = Vec::new();
+
+        if self.eat(&Token::Rcurl) {
+            return Ok(Filter::Object(members));
+        }
+
+        loop {
+            let (key, shorthand) = match self.next() {
+                Some(Token::Ident(name) | Token::String(name)) => {
+                    let shorthand = Filter::Index(
+                        Box::new(Filter::Identity),
+                        Box::new(Filter::string(name.clone())),
+                    );
+                    (Filter::string(name), Some(shorthand))
+                }
+                Some(Token::Lparen) => {
+                    let key = self.parse_pipe()?;
+                    self.expect(&Token::Rparen, "')'")?;
+                    (key, None)
+                }
+                _ => {
+                    self.pos = self.pos.saturating_sub(1);
+                    return Err(self.unexpected("an object key"));
+                }
+            };
+
+            let value = if self.eat(&Token::Colon) {
+                self.parse_or()?
+            } else if let Some(shorthand) = shorthand {
+                shorthand
+            } else {
+                return Err(self.unexpected("':'"));
+            };
+
+            members.push((key, value));
+
+            if self.eat(&Token::Rcurl) {
+                return Ok(Filter::Object(members));
+            }
+
+            self.expect(&Token::Comma, "',' or '}'")?;
+        }
+    }
+
+    /// Parses a keyword literal or a call to a builtin, with arguments
+    /// separated by `;`.
+    fn parse_call(&mut self, name: String, col: usize) -> Result<Filter> {
+        match name.as_str() {
+            "true" => return Ok(Filter::Literal(Value::Boolean(true))),
+            "false" => return Ok(Filter::Literal(Value::Boolean(false))),
+            "null" => return Ok(Filter::Literal(Value::Null)),
+            _ => {}
+        }
+
+        let mut args = Vec::new();
+
+        if self.eat(&Token::Lparen) {
+            loop {
+                args.push(self.parse_pipe()?);
+
+                if self.eat(&Token::Rparen) {
+                    break;
+                }
+
+                self.expect(&Token::Semicolon, "';' or ')'")?;
+            }
+        }
+
+        let builtin = match (name.as_str(), args.len()) {
+            ("empty", 0) => Builtin::Empty,
+            ("not", 0) => Builtin::Not,
+            ("length", 0) => Builtin::Length,
+            ("keys", 0) => Builtin::Keys,
+            ("add", 0) => Builtin::Add,
+            ("type", 0) => Builtin::Type,
+            ("select", 1) => Builtin::Select,
+            ("map", 1) => Builtin::Map,
+            ("has", 1) => Builtin::Has,
+            _ => {
+                return Err(JqError::Syntax {
+                    message: format!("Unknown function {name}/{}", args.len()),
+                    col,
+                });
+            }
+        };
+
+        Ok(Filter::Call(builtin, args))
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        match self.peek() {
+            Some(Token::Ident(name)) if name == keyword => {
+                self.pos += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn expect(&mut self, token: &Token, expected: &str) -> Result<()> {
+        if self.eat(token) {
+            Ok(())
+        } else {
+            Err(self.unexpected(expected))
+        }
+    }
+
+    fn eat(&mut self, token: &Token) -> bool {
+        if self.peek() == Some(token) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).map(|(token, _)| token.clone());
+        self.pos += 1;
+
+        token
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.peek_at(0)
+    }
+
+    fn peek_at(&self, n: usize) -> Option<&Token> {
+        self.tokens.get(self.pos + n).map(|(token, _)| token)
+    }
+
+    fn col(&self) -> usize {
+        self.tokens
+            .get(self.pos)
+            .map_or(self.end_col, |&(_, col)| col)
+    }
+
+    fn unexpected(&self, expected: &str) -> JqError {
+        let found = match self.peek() {
+            Some(token) => format!("{token:?}"),
+            None => "the end of the filter".to_string(),
+        };
+
+        JqError::Syntax {
+            message: format!("Expected {expected}, found {found}"),
+            col: self.col(),
+        }
+    }
+}
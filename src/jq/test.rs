@@ -0,0 +1,224 @@
+use super::*;
+use crate::parser::{parse, parse_deep};
+
+/// Runs `filter` on `input` and returns the outputs as compact json.
+fn run(filter: &str, input: &str) -> Vec<String> {
+    let filter: JqFilter = filter
+        .parse()
+        .unwrap_or_else(|e| panic!("Failed to parse {filter:?}: {e}"));
+
+    filter
+        .apply(&parse(input))
+        .unwrap_or_else(|e| panic!("Failed to run {filter:?}: {e}"))
+        .iter()
+        .map(Value::to_string)
+        .collect()
+}
+
+fn run_err(filter: &str, input: &str) -> JqError {
+    let filter: JqFilter = filter.parse().expect("Parsing failed");
+
+    filter.apply(&parse(input)).expect_err("Expected an error")
+}
+
+#[test]
+fn paths_and_iteration() {
+    let input = r#"{"a": {"b": [1, 2, 3]}, "c d": true}"#;
+
+    assert_eq!(run(".", "1"), ["1"]);
+    assert_eq!(run(".a.b", input), ["[1,2,3]"]);
+    assert_eq!(run(".a.b[0], .a.b[-1]", input), ["1", "3"]);
+    assert_eq!(run(r#"."c d", .["c d"]"#, input), ["true", "true"]);
+    assert_eq!(run(".a.b[]", input), ["1", "2", "3"]);
+    assert_eq!(run(".a.b[1:], .a.b[:-1]", input), ["[2,3]", "[1,2]"]);
+    assert_eq!(run(r#".[1:3]"#, r#""héllo""#), [r#""él""#]);
+    assert_eq!(run(".missing.deeper, .a.b[9]", input), ["null", "null"]);
+    assert_eq!(run("[..] | length", input), ["7"]);
+    assert_eq!(run("[..]", "[[1], 2]"), ["[[[1],2],[1],1,2]"]);
+}
+
+#[test]
+fn pipes_and_construction() {
+    let input = r#"{"items": [{"id": 1, "tags": ["x"]}, {"id": 2, "tags": []}]}"#;
+
+    assert_eq!(run(".items[] | .id", input), ["1", "2"]);
+    assert_eq!(run("[.items[].id]", input), ["[1,2]"]);
+    assert_eq!(
+        run(".items[] | {id, count: (.tags | length)}", input),
+        [r#"{"id":1,"count":1}"#, r#"{"id":2,"count":0}"#]
+    );
+    assert_eq!(
+        run("{(.items[0].tags[0]): .items[0].id}", input),
+        [r#"{"x":1}"#]
+    );
+}
+
+#[test]
+fn object_construction_takes_the_cartesian_product() {
+    assert_eq!(
+        run(r#"{a: (1, 2), "b": (3, 4)}"#, "null"),
+        [
+            r#"{"a":1,"b":3}"#,
+            r#"{"a":1,"b":4}"#,
+            r#"{"a":2,"b":3}"#,
+            r#"{"a":2,"b":4}"#
+        ]
+    );
+}
+
+#[test]
+fn builtins() {
+    let input = r#"[{"n": 3, "ok": true}, {"n": 1, "ok": false}, {"n": 2, "ok": true}]"#;
+
+    assert_eq!(run("map(select(.ok) | .n)", input), ["[3,2]"]);
+    assert_eq!(run("map(.n) | add", input), ["6"]);
+    assert_eq!(run(".[0] | keys", input), [r#"["n","ok"]"#]);
+    assert_eq!(run("keys", "[5, 6]"), ["[0,1]"]);
+    assert_eq!(run("length", input), ["3"]);
+    assert_eq!(
+        run(r#""héllo", {"a": 1}, null, -4 | length"#, "null"),
+        ["5", "1", "0", "4"]
+    );
+    assert_eq!(
+        run(r#".[0] | has("n"), has("x")"#, input),
+        ["true", "false"]
+    );
+    assert_eq!(run(".[] | .ok | not", input), ["false", "true", "false"]);
+    assert_eq!(
+        run("[.[] | type], (1, empty, 2)", "[1, \"a\", null]"),
+        [r#"["number","string","null"]"#, "1", "2"]
+    );
+}
+
+#[test]
+fn arithmetic() {
+    assert_eq!(run("1 + 2 * 3, (1 + 2) * 3", "null"), ["7", "9"]);
+    assert_eq!(
+        run("7 / 2, 6 / 3, 7 % 3, -7 % 3", "null"),
+        ["3.5", "2", "1", "-1"]
+    );
+    assert_eq!(
+        run(".a - .b, -.a", r#"{"a": 0.5, "b": 2}"#),
+        ["-1.5", "-0.5"]
+    );
+    assert_eq!(run("9007199254740993 + 1", "null"), ["9007199254740994"]);
+    assert_eq!(run("0.1 + 0.2", "null"), ["0.30000000000000004"]);
+    assert_eq!(run("1 - 2 - 3", "null"), ["-4"]);
+}
+
+#[test]
+fn arithmetic_on_other_types() {
+    assert_eq!(run(r#""a" + "b", null + 1"#, "null"), [r#""ab""#, "1"]);
+    assert_eq!(
+        run("[1, 2, 1, 3] - [1], [1] + [2]", "null"),
+        ["[2,3]", "[1,2]"]
+    );
+    assert_eq!(
+        run(r#"{"a": 1, "b": 2} + {"b": 3}"#, "null"),
+        [r#"{"a":1,"b":3}"#]
+    );
+    assert_eq!(
+        run(r#"{"a": {"x": 1}} * {"a": {"y": 2}}"#, "null"),
+        [r#"{"a":{"x":1,"y":2}}"#]
+    );
+    assert_eq!(run(r#""a,b,c" / ",""#, "null"), [r#"["a","b","c"]"#]);
+}
+
+#[test]
+fn comparisons_and_logic() {
+    assert_eq!(
+        run("1 < 2, 1.0 == 1, 2 >= 3, 1 != 1", "null"),
+        ["true", "true", "false", "false"]
+    );
+    assert_eq!(
+        run(
+            r#"null < false, false < true, true < 0, 0 < "", "" < [], [] < {}"#,
+            "null"
+        ),
+        ["true"; 6]
+    );
+    assert_eq!(
+        run("[1, 2] < [1, 3], [1] < [1, 0]", "null"),
+        ["true", "true"]
+    );
+    assert_eq!(
+        run(r#"{"a": 2} > {"a": 1}, {"a": 9} < {"b": 0}"#, "null"),
+        ["true", "true"]
+    );
+    assert_eq!(
+        run("true and (1, null), false or false", "null"),
+        ["true", "false", "false"]
+    );
+    assert_eq!(run("false and .a, true or .a", "1"), ["false", "true"]);
+}
+
+#[test]
+fn binary_operators_iterate_both_sides() {
+    assert_eq!(run("(1, 2) + (10, 20)", "null"), ["11", "12", "21", "22"]);
+}
+
+#[test]
+fn runtime_errors() {
+    assert_eq!(
+        run_err(".a", "1"),
+        JqError::Runtime(r#"Cannot index number with "a""#.to_string())
+    );
+    assert_eq!(
+        run_err(".[]", "true"),
+        JqError::Runtime("Cannot iterate over boolean".to_string())
+    );
+    assert_eq!(
+        run_err(r#"1 + "a""#, "null"),
+        JqError::Runtime(r#"number (1) and string ("a") cannot be added"#.to_string())
+    );
+    assert!(matches!(run_err("1 / 0", "null"), JqError::Runtime(_)));
+    assert_eq!(run(".a?, [.[]?]", "1"), ["[]"]);
+}
+
+#[test]
+fn syntax_errors() {
+    let col = |filter: &str| match filter.parse::<JqFilter>() {
+        Err(JqError::Syntax { col, .. }) => col,
+        other => panic!("Expected a syntax error for {filter:?}, got {other:?}"),
+    };
+
+    assert_eq!(col(".a |"), 5);
+    assert_eq!(col(".a | frobnicate"), 6);
+    assert_eq!(col("[1, 2"), 6);
+    assert_eq!(col("1 < 2 < 3"), 7);
+    assert_eq!(col(r#""unterminated"#), 1);
+    assert_eq!(col("{(1)}"), 5);
+    assert_eq!(col(". $"), 3);
+}
+
+#[test]
+fn paths_borrow_from_the_input() {
+    let input = parse(r#"{"a": {"b": [{"c": 1}, 2]}}"#);
+
+    for filter in [".", ".a.b[0]", ".a | .b[]", ".a.b[] | select(. != 2)", ".."] {
+        let filter: JqFilter = filter.parse().unwrap();
+        let outputs = filter.filter.eval(&input).unwrap();
+
+        assert!(
+            outputs
+                .iter()
+                .all(|output| matches!(output, Cow::Borrowed(_)))
+        );
+    }
+
+    let filter: JqFilter = "[.a.b[]] | .[0].c".parse().unwrap();
+    assert_eq!(filter.apply(&input).unwrap(), [parse("1")]);
+}
+
+#[test]
+fn very_deep_inputs() {
+    let input = parse_deep(r#"{"a":"#, "null");
+
+    for filter in [".", ". * ."] {
+        let filter: JqFilter = filter.parse().unwrap();
+        assert_eq!(filter.apply(&input).unwrap(), std::slice::from_ref(&input));
+    }
+
+    let filter: JqFilter = ". == .".parse().unwrap();
+    assert_eq!(filter.apply(&input).unwrap(), [Value::Boolean(true)]);
+}
@@ -17,6 +17,8 @@ impl std::fmt::Display for LexerError {
     }
 }
 
+impl std::error::Error for LexerError {}
+
 #[derive(Debug, Error)]
 pub enum LexerErrorKind {
     #[error("[invalid string] {0}")]
@@ -43,6 +45,12 @@ pub enum StringError {
     UnescapedControlCharacter { code: u8 },
     #[error("unicode escape must be followed by four hexadecimal digits, found '{digits}'")]
     InvalidUnicodeEscape { digits: String },
+    #[error("string contains unpaired UTF-16 surrogate {}", format_units(units))]
+    UnpairedSurrogate { units: Vec<u16> },
+}
+
+fn format_units(units: &[u16]) -> String {
+    units.iter().map(|unit| format!("\\u{unit:04X}")).collect()
 }
 
 #[derive(Error, Debug)]
@@ -82,9 +82,42 @@ impl<'a> Lexer<'a> {
     }
 
     pub fn lex(&mut self) -> std::result::Result<Vec<Token>, LexerError> {
+        self.lex_tokens(None)
+    }
+
+    /// Lexes the input and also returns the byte range covered by each
+    /// token, in the same order as the tokens.
+    pub fn lex_with_spans(
+        &mut self,
+    ) -> std::result::Result<(Vec<Token>, Vec<Range<usize>>), LexerError> {
+        let mut spans = vec![];
+        let tokens = self.lex_tokens(Some(&mut spans))?;
+
+        Ok((tokens, spans))
+    }
+
+    /// Lexes the next token and returns the byte range it covers, or `None`
+    /// at the end of the input or if the token is invalid.
+    pub fn next_span(&mut self) -> Option<Range<usize>> {
+        self.skip_whitespace();
+        let start = self.pos;
+
+        match self.next_token() {
+            Ok(Token::Eof) | Err(_) => None,
+            Ok(_) => Some(start..self.pos),
+        }
+    }
+
+    fn lex_tokens(
+        &mut self,
+        mut spans: Option<&mut Vec<Range<usize>>>,
+    ) -> std::result::Result<Vec<Token>, LexerError> {
         let mut tokens = vec![];
 
         loop {
+            self.skip_whitespace();
+            let start = self.pos;
+
             match self.next_token() {
                 Err(e) => {
                     return Err(LexerError {
@@ -94,7 +127,13 @@ impl<'a> Lexer<'a> {
                     });
                 }
                 Ok(Token::Eof) => return Ok(tokens),
-                Ok(token) => tokens.push(token),
+                Ok(token) => {
+                    if let Some(spans) = spans.as_deref_mut() {
+                        spans.push(start..self.pos);
+                    }
+
+                    tokens.push(token);
+                }
             }
         }
     }
@@ -265,7 +304,7 @@ impl<'a> Lexer<'a> {
 
                     match escape {
                         b'"' | b'\\' | b'/' | b'b' | b'f' | b'n' | b'r' | b't' => self.advance()?,
-                        b'u' => self.read_unicode_escape()?,
+                        b'u' => self.read_unicode_sequence()?,
                         other => {
                             return Err(LexerErrorKind::InvalidString(
                                 StringError::InvalidEscape { escape: other },
@@ -285,7 +324,38 @@ impl<'a> Lexer<'a> {
         Err(LexerErrorKind::InvalidString(StringError::Unterminated))
     }
 
-    fn read_unicode_escape(&mut self) -> Result<()> {
+    fn read_unicode_sequence(&mut self) -> Result<()> {
+        let unit = self.read_unicode_escape()?;
+
+        match unit {
+            0xD800..=0xDBFF => {
+                if self.curr() != Some(b'\\') || self.peek() != Some(b'u') {
+                    return Err(LexerErrorKind::InvalidString(
+                        StringError::UnpairedSurrogate { units: vec![unit] },
+                    ));
+                }
+
+                self.advance()?;
+                let low = self.read_unicode_escape()?;
+
+                if !(0xDC00..=0xDFFF).contains(&low) {
+                    return Err(LexerErrorKind::InvalidString(
+                        StringError::UnpairedSurrogate {
+                            units: vec![unit, low],
+                        },
+                    ));
+                }
+
+                Ok(())
+            }
+            0xDC00..=0xDFFF => Err(LexerErrorKind::InvalidString(
+                StringError::UnpairedSurrogate { units: vec![unit] },
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    fn read_unicode_escape(&mut self) -> Result<u16> {
         self.advance()?;
         let start = self.pos;
 
@@ -311,7 +381,8 @@ impl<'a> Lexer<'a> {
             }
         }
 
-        Ok(())
+        let digits = str::from_utf8(&self.input[start..self.pos]).expect("hex digits are ascii");
+        Ok(u16::from_str_radix(digits, 16).expect("four hex digits fit in a u16"))
     }
 
     fn advance(&mut self) -> Result<()> {
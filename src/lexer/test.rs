@@ -82,6 +82,22 @@ fn literals() {
     });
 }
 
+#[test]
+fn spans() {
+    let input = "{ \"key\" :\n  [true, -1.5] }";
+    let (tokens, spans) = Lexer::new(input.as_bytes())
+        .lex_with_spans()
+        .expect("expected lexer to succeed");
+
+    let slices: Vec<_> = spans.iter().map(|span| &input[span.clone()]).collect();
+
+    assert_eq!(tokens.len(), spans.len());
+    assert_eq!(
+        slices,
+        ["{", "\"key\"", ":", "[", "true", ",", "-1.5", "]", "}"]
+    );
+}
+
 #[test]
 fn empty_input() {
     let tokens = expect_success("");
@@ -165,6 +181,47 @@ fn rejects_string_with_short_unicode_escape() {
     assert_eq!(col, 6);
 }
 
+#[test]
+fn string_with_surrogate_pair() {
+    let input = r#""\uD83D\uDE00""#;
+    let tokens = expect_success(input);
+
+    let expected = [Token::String(1..input.len() - 1)];
+    assert_eq!(tokens, expected);
+}
+
+#[test]
+fn rejects_lone_high_surrogate() {
+    let LexerError { kind, line, col } = expect_error(r#""\uD800""#);
+
+    assert!(matches!(
+        kind,
+        InvalidString(StringError::UnpairedSurrogate { units }) if units == [0xD800]
+    ));
+    assert_eq!(line, 1);
+    assert_eq!(col, 8);
+}
+
+#[test]
+fn rejects_high_surrogate_followed_by_non_low_surrogate() {
+    let LexerError { kind, .. } = expect_error(r#""\uD800\u0041""#);
+
+    assert!(matches!(
+        kind,
+        InvalidString(StringError::UnpairedSurrogate { units }) if units == [0xD800, 0x0041]
+    ));
+}
+
+#[test]
+fn rejects_lone_low_surrogate() {
+    let LexerError { kind, .. } = expect_error(r#""\uDC00""#);
+
+    assert!(matches!(
+        kind,
+        InvalidString(StringError::UnpairedSurrogate { units }) if units == [0xDC00]
+    ));
+}
+
 #[test]
 fn rejects_number_with_leading_zero() {
     let LexerError { kind, line, col } = expect_error("01");
@@ -1,5 +1,23 @@
+pub mod diagnostic;
+pub mod jq;
 pub mod lexer;
 pub mod parser;
+pub mod patch;
+pub mod path;
+pub mod pointer;
+pub mod serializer;
 
+pub use diagnostic::Diagnostic;
+pub use jq::JqFilter;
 pub use lexer::Lexer;
 pub use parser::Parser;
+pub use patch::JsonPatch;
+pub use path::JsonPath;
+pub use pointer::JsonPointer;
+
+#[cfg(feature = "serde")]
+pub use parser::{from_slice, from_str};
+#[cfg(feature = "serde")]
+pub use serializer::ser::{
+    to_string, to_string_pretty, to_value, to_writer, to_writer_pretty, to_writer_with_options,
+};
@@ -1,11 +1,24 @@
+mod commands;
+
 use std::fmt::Display;
+use std::fs;
 use std::path::PathBuf;
+use std::process::ExitCode;
 use std::time::Instant;
-use std::{fs, thread};
 
-use clap::Parser as ClapParser;
+use clap::{Args as ClapArgs, Parser as ClapParser, Subcommand};
+use commands::ParserArgs;
+use commands::diff::DiffArgs;
+use commands::format::FormatArgs;
+use commands::get::GetArgs;
+use commands::jq::JqArgs;
+use commands::merge::MergeArgs;
+use commands::minify::MinifyArgs;
+use commands::patch::PatchArgs;
+use commands::query::QueryArgs;
 use humansize::{DECIMAL, format_size};
-use json_parser::{Lexer, Parser};
+use json_parser::parser::ParserOptions;
+use json_parser::{Diagnostic, Lexer, Parser};
 use num_format::{Locale, ToFormattedString};
 use rayon::prelude::*;
 
@@ -13,7 +26,38 @@ use rayon::prelude::*;
 #[command(name = "json-parser")]
 #[command(about = "A JSON parser written in Rust")]
 #[command(version)]
+#[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
 struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    #[command(flatten)]
+    validate: ValidateArgs,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Rewrites json files with consistent formatting
+    Format(FormatArgs),
+    /// Strips insignificant whitespace without building the document in memory
+    Minify(MinifyArgs),
+    /// Prints the value addressed by a json pointer
+    Get(GetArgs),
+    /// Prints the values selected by a JSONPath query in each file
+    Query(QueryArgs),
+    /// Transforms each file with a jq-like filter
+    Jq(JqArgs),
+    /// Applies an RFC 6902 json patch and prints the result
+    Patch(PatchArgs),
+    /// Shows the changes between two json files, optionally as a json patch
+    Diff(DiffArgs),
+    /// Layers RFC 7386 merge patches over a base document and prints the result
+    Merge(MergeArgs),
+}
+
+/// Validates files when no subcommand is given.
+#[derive(ClapArgs)]
+struct ValidateArgs {
     #[arg(required = true)]
     files: Vec<PathBuf>,
 
@@ -25,46 +69,55 @@ struct Args {
 
     #[arg(short, long, help = "Processes files sequentially using 1 thread")]
     sequential: bool,
+
+    #[command(flatten)]
+    parser: ParserArgs,
 }
 
-fn main() {
+fn main() -> ExitCode {
     let args = Args::parse();
 
-    let num_threads = if args.sequential {
-        1
-    } else {
-        thread::available_parallelism()
-            .map(|n| n.get())
-            .unwrap_or(1)
-            .clamp(1, args.files.len())
-    };
+    match args.command {
+        Some(Command::Format(args)) => commands::format::run(args),
+        Some(Command::Minify(args)) => commands::minify::run(args),
+        Some(Command::Get(args)) => commands::get::run(args),
+        Some(Command::Query(args)) => commands::query::run(args),
+        Some(Command::Jq(args)) => commands::jq::run(args),
+        Some(Command::Patch(args)) => commands::patch::run(args),
+        Some(Command::Diff(args)) => commands::diff::run(args),
+        Some(Command::Merge(args)) => commands::merge::run(args),
+        None => {
+            if validate(args.validate) {
+                ExitCode::SUCCESS
+            } else {
+                ExitCode::FAILURE
+            }
+        }
+    }
+}
 
-    let results: Vec<Result<ParseResult, String>> = if num_threads == 1 {
-        println!("Processing {} files sequentially", args.files.len());
+/// Returns whether every file could be read and was valid.
+fn validate(args: ValidateArgs) -> bool {
+    let options = args.parser.options();
 
-        args.files
-            .iter()
-            .map(|file| parse_file(file, args.tokens))
-            .collect()
+    let pool = commands::thread_pool(args.sequential, args.files.len());
+
+    if pool.current_num_threads() == 1 {
+        println!("Processing {} files sequentially", args.files.len());
     } else {
         println!(
             "Processing {} files in parallel using {} threads",
             args.files.len(),
-            num_threads
+            pool.current_num_threads()
         );
+    }
 
-        let pool = rayon::ThreadPoolBuilder::new()
-            .num_threads(num_threads)
-            .build()
-            .expect("Failed to build thread pool");
-
-        pool.install(|| {
-            args.files
-                .par_iter()
-                .map(|file| parse_file(file, args.tokens))
-                .collect()
-        })
-    };
+    let results: Vec<Result<ParseResult, String>> = pool.install(|| {
+        args.files
+            .par_iter()
+            .map(|file| parse_file(file, args.tokens, &options))
+            .collect()
+    });
 
     results
         .iter()
@@ -79,6 +132,7 @@ fn main() {
             eprintln!("Failed to process {file}: {error}");
         });
 
+    let all_read = results.iter().all(Result::is_ok);
     let results: Vec<_> = results.iter().filter_map(|r| r.as_ref().ok()).collect();
 
     results.iter().for_each(|result| {
@@ -110,12 +164,14 @@ fn main() {
     println!("Total files: {}", results.len());
     println!("Valid files: {valid_files}");
     println!("Invalid files: {invalid_files}");
+
+    all_read && results.iter().all(|r| r.outcome.is_valid())
 }
 
 #[derive(Debug, PartialEq)]
 enum Outcome {
     Valid,
-    Invalid { error_message: String },
+    Invalid { diagnostic: String },
 }
 
 impl Outcome {
@@ -128,9 +184,7 @@ impl Display for Outcome {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let s = match self {
             Outcome::Valid => "valid".to_string(),
-            Outcome::Invalid { error_message } => {
-                format!("invalid\nError message: {error_message}")
-            }
+            Outcome::Invalid { diagnostic } => format!("invalid\n{}", diagnostic.trim_end()),
         };
 
         write!(f, "{s}")
@@ -188,20 +242,25 @@ impl ParseResult {
     }
 }
 
-fn parse_file(file_path: &PathBuf, output_tokens: bool) -> Result<ParseResult, String> {
+fn parse_file(
+    file_path: &PathBuf,
+    output_tokens: bool,
+    options: &ParserOptions,
+) -> Result<ParseResult, String> {
     let input = fs::read(file_path).map_err(|e| format!("Error reading file: {e}"))?;
     let mut lexer = Lexer::new(&input);
 
     let lex_start = Instant::now();
-    let tokens = match lexer.lex() {
-        Ok(tokens) => tokens,
+    let (tokens, spans) = match lexer.lex_with_spans() {
+        Ok(lexed) => lexed,
         Err(e) => {
             return Ok(ParseResult::new(
                 file_path.clone(),
                 input.len(),
                 0,
                 Outcome::Invalid {
-                    error_message: e.to_string(),
+                    diagnostic: Diagnostic::from_lexer_error(&e)
+                        .render(&input, &file_path.display().to_string()),
                 },
                 lex_start.elapsed().as_secs_f64(),
                 0.0,
@@ -231,26 +290,16 @@ fn parse_file(file_path: &PathBuf, output_tokens: bool) -> Result<ParseResult, S
     }
 
     let token_count = tokens.len();
-    let mut parser = Parser::new(tokens, &input);
+    let mut parser = Parser::with_spans(tokens, spans, &input, options.clone());
     let parse_start = Instant::now();
     let value = parser.parse();
     let parse_duration = parse_start.elapsed().as_secs_f64();
 
-    let Some(value) = value else {
-        return Ok(ParseResult::new(
-            file_path.clone(),
-            input.len(),
-            token_count,
-            Outcome::Valid,
-            lex_duration,
-            parse_duration,
-        ));
-    };
-
     let outcome = match value {
         Ok(_) => Outcome::Valid,
         Err(e) => Outcome::Invalid {
-            error_message: e.to_string(),
+            diagnostic: Diagnostic::from_parser_error(&e, &input)
+                .render(&input, &file_path.display().to_string()),
         },
     };
 
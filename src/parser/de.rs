@@ -0,0 +1,870 @@
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::marker::PhantomData;
+
+use serde::de::value::BorrowedStrDeserializer;
+use serde::de::{
+    self, DeserializeSeed, EnumAccess, IgnoredAny, IntoDeserializer, MapAccess, SeqAccess,
+    Unexpected, VariantAccess, Visitor,
+};
+use serde::forward_to_deserialize_any;
+use thiserror::Error;
+
+use super::number::NUMBER_TOKEN;
+use super::{
+    Array, DuplicateKeys, Number, Object, Parser, ParserError, ParserErrorKind, ParserOptions,
+    Value, error,
+};
+use crate::lexer::{Lexer, LexerError, Token};
+
+/// The name of the newtype struct a [`Value`] asks for, which tells this
+/// crate's deserializer to hand over numbers as their exact text and to apply
+/// its duplicate key policy.
+const VALUE_TOKEN: &str = "$json_parser::private::Value";
+
+/// The enum variant that wraps an object whose repeated keys must all be
+/// kept. Like [`NUMBER_TOKEN`], it is only ever sent to a [`Value`], and
+/// never for anything in the input since json has no enums.
+const KEEP_ALL_TOKEN: &str = "$json_parser::private::KeepAll";
+
+#[derive(Debug, Error)]
+pub enum DeserializeError {
+    #[error("{0}")]
+    Lexer(LexerError),
+    #[error("{0}")]
+    Parser(#[from] ParserError),
+    /// An error reported by a `Deserialize` implementation, such as a missing
+    /// field or a value of the wrong type, located at the start of the value
+    /// being deserialized. The position is 0 if it could not be determined.
+    #[error("Error at line {line}, col {col}: {message}")]
+    Custom {
+        message: String,
+        line: usize,
+        col: usize,
+    },
+}
+
+impl de::Error for DeserializeError {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        Self::Custom {
+            message: msg.to_string(),
+            line: 0,
+            col: 0,
+        }
+    }
+}
+
+type Result<T> = std::result::Result<T, DeserializeError>;
+
+/// Deserializes an instance of `T` from a json document.
+///
+/// Strings without escape sequences are borrowed from `input`, so `T` may
+/// contain `&'a str` fields as long as the matching strings are not escaped.
+pub fn from_slice<'a, T: de::Deserialize<'a>>(input: &'a [u8]) -> Result<T> {
+    let (tokens, spans) = Lexer::new(input)
+        .lex_with_spans()
+        .map_err(DeserializeError::Lexer)?;
+    let mut parser = Parser::with_spans(tokens, spans, input, ParserOptions::default());
+
+    if parser.curr().is_none() {
+        return Err(parser.error(ParserErrorKind::EmptyDocument).into());
+    }
+
+    let value = T::deserialize(&mut parser)?;
+    parser.check_end()?;
+
+    Ok(value)
+}
+
+/// Deserializes an instance of `T` from a json string, see [`from_slice`].
+pub fn from_str<'a, T: de::Deserialize<'a>>(input: &'a str) -> Result<T> {
+    from_slice(input.as_bytes())
+}
+
+impl<'de> Parser<'de> {
+    /// Locates an error raised by a visitor at the token `start`, unless an
+    /// inner value has already done so.
+    fn locate(&self, error: DeserializeError, start: usize) -> DeserializeError {
+        match error {
+            DeserializeError::Custom {
+                message,
+                line: 0,
+                col: 0,
+            } => {
+                let (line, col) = error::line_col(self.input, self.offset_of(start));
+                DeserializeError::Custom { message, line, col }
+            }
+            error => error,
+        }
+    }
+
+    fn visit_number<V: Visitor<'de>>(&self, number: &str, visitor: V) -> Result<V::Value> {
+        if number.contains(['.', 'e', 'E']) {
+            return visitor.visit_f64(Number::from_raw(number).as_f64_lossy());
+        }
+
+        if let Ok(n) = number.parse() {
+            visitor.visit_u64(n)
+        } else if let Ok(n) = number.parse() {
+            visitor.visit_i64(n)
+        } else if let Ok(n) = number.parse() {
+            visitor.visit_u128(n)
+        } else if let Ok(n) = number.parse() {
+            visitor.visit_i128(n)
+        } else {
+            visitor.visit_f64(Number::from_raw(number).as_f64_lossy())
+        }
+    }
+
+    /// Deserializes an object or array. Object members follow `policy` when
+    /// it is given, and are otherwise all handed to the visitor.
+    fn deserialize_nested<V: Visitor<'de>>(
+        &mut self,
+        visitor: V,
+        policy: Option<DuplicateKeys>,
+    ) -> Result<V::Value> {
+        self.check_depth(self.depth)?;
+
+        self.depth += 1;
+        let value = if self.curr() == Some(&Token::Lcurl) {
+            self.visit_members(visitor, policy)
+        } else {
+            self.visit_elements(visitor)
+        };
+        self.depth -= 1;
+
+        value
+    }
+
+    fn visit_members<V: Visitor<'de>>(
+        &mut self,
+        visitor: V,
+        policy: Option<DuplicateKeys>,
+    ) -> Result<V::Value> {
+        self.next()?;
+
+        let mut members = Members {
+            parser: self,
+            first: true,
+            done: false,
+            seen: matches!(
+                policy,
+                Some(DuplicateKeys::Error | DuplicateKeys::FirstWins)
+            )
+            .then(HashSet::new),
+        };
+        let value = visitor.visit_map(&mut members)?;
+
+        if !members.done {
+            self.end_container(&Token::Rcurl, "object has more members than expected")?;
+        }
+
+        Ok(value)
+    }
+
+    fn visit_elements<V: Visitor<'de>>(&mut self, visitor: V) -> Result<V::Value> {
+        self.next()?;
+
+        let mut elements = Elements {
+            parser: self,
+            first: true,
+            done: false,
+        };
+        let value = visitor.visit_seq(&mut elements)?;
+
+        if !elements.done {
+            self.end_container(&Token::Rsquare, "array has more elements than expected")?;
+        }
+
+        Ok(value)
+    }
+
+    /// Consumes the closing token of a container whose visitor stopped before
+    /// reaching the end of it.
+    fn end_container(&mut self, close: &Token, too_long: &str) -> Result<()> {
+        if self.curr() == Some(&Token::Comma) {
+            return Err(de::Error::custom(too_long));
+        }
+
+        self.parse_separator(close)?;
+        Ok(())
+    }
+}
+
+impl<'de> de::Deserializer<'de> for &mut Parser<'de> {
+    type Error = DeserializeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        let start = self.pos;
+        let Some(token) = self.curr() else {
+            return Err(self.error(ParserErrorKind::UnexpectedEof).into());
+        };
+
+        let result = match token {
+            Token::Lcurl | Token::Lsquare => self.deserialize_nested(visitor, None),
+            Token::String(range) => {
+                let range = range.clone();
+                self.next()?;
+
+                match self.read_string(&range)? {
+                    Cow::Borrowed(s) => visitor.visit_borrowed_str(s),
+                    Cow::Owned(s) => visitor.visit_string(s),
+                }
+            }
+            Token::Number(range) => {
+                let number = self.read_str(&range.clone())?;
+                self.next()?;
+                self.visit_number(number, visitor)
+            }
+            Token::True | Token::False => {
+                let b = token == &Token::True;
+                self.next()?;
+                visitor.visit_bool(b)
+            }
+            Token::Null => {
+                self.next()?;
+                visitor.visit_unit()
+            }
+            _ => {
+                return Err(self
+                    .error(ParserErrorKind::InvalidValue {
+                        found: token.clone(),
+                    })
+                    .into());
+            }
+        };
+
+        result.map_err(|e| self.locate(e, start))
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        if self.curr() == Some(&Token::Null) {
+            self.next()?;
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value> {
+        if name == VALUE_TOKEN {
+            return visitor.visit_newtype_struct(ValueDeserializer(self));
+        }
+
+        visitor.visit_newtype_struct(self)
+    }
+
+    /// Unit variants are written as a string and all other variants as an
+    /// object with the variant name as its only key.
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        let start = self.pos;
+
+        let result = match self.curr() {
+            Some(Token::String(range)) => {
+                let variant = self.read_string(&range.clone())?;
+                self.next()?;
+                visitor.visit_enum(variant.into_deserializer())
+            }
+            Some(Token::Lcurl) => {
+                self.check_depth(self.depth)?;
+                self.next()?;
+                self.depth += 1;
+                let value = visitor.visit_enum(Variant { parser: self });
+                self.depth -= 1;
+
+                value.and_then(|value| {
+                    if self.curr() == Some(&Token::Rcurl) {
+                        self.next()?;
+                        Ok(value)
+                    } else {
+                        Err(de::Error::custom(
+                            "expected an object with a single key naming the variant",
+                        ))
+                    }
+                })
+            }
+            _ => Err(de::Error::custom(
+                "expected a string or an object for an enum",
+            )),
+        };
+
+        result.map_err(|e| self.locate(e, start))
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct
+        identifier ignored_any
+    }
+}
+
+struct Elements<'p, 'de> {
+    parser: &'p mut Parser<'de>,
+    first: bool,
+    done: bool,
+}
+
+impl<'de> SeqAccess<'de> for Elements<'_, 'de> {
+    type Error = DeserializeError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>> {
+        if self.done {
+            return Ok(None);
+        }
+
+        let closed = if self.first {
+            self.first = false;
+
+            if self.parser.curr() == Some(&Token::Rsquare) {
+                self.parser.next()?;
+                true
+            } else {
+                false
+            }
+        } else {
+            self.parser.parse_separator(&Token::Rsquare)?
+        };
+
+        if closed {
+            self.done = true;
+            return Ok(None);
+        }
+
+        seed.deserialize(&mut *self.parser).map(Some)
+    }
+}
+
+struct Members<'p, 'de> {
+    parser: &'p mut Parser<'de>,
+    first: bool,
+    done: bool,
+    /// The keys so far, when repeated keys are rejected or skipped.
+    seen: Option<HashSet<Cow<'de, str>>>,
+}
+
+impl<'de> Members<'_, 'de> {
+    /// Whether the key at the current token was already seen and the member
+    /// should be skipped. Fails if repeated keys are rejected.
+    fn is_repeated(&mut self) -> Result<bool> {
+        let (Some(seen), Some(Token::String(range))) = (&mut self.seen, self.parser.curr()) else {
+            return Ok(false);
+        };
+
+        let key = self.parser.read_string(&range.clone())?;
+
+        if seen.insert(key.clone()) {
+            Ok(false)
+        } else if self.parser.options.duplicate_keys == DuplicateKeys::Error {
+            Err(self.parser.duplicate_key_error(key).into())
+        } else {
+            Ok(true)
+        }
+    }
+}
+
+impl<'de> MapAccess<'de> for Members<'_, 'de> {
+    type Error = DeserializeError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>> {
+        loop {
+            if self.done {
+                return Ok(None);
+            }
+
+            let closed = if self.first {
+                self.first = false;
+
+                if self.parser.curr() == Some(&Token::Rcurl) {
+                    self.parser.next()?;
+                    true
+                } else {
+                    false
+                }
+            } else {
+                self.parser.parse_separator(&Token::Rcurl)?
+            };
+
+            if closed {
+                self.done = true;
+                return Ok(None);
+            }
+
+            if !self.is_repeated()? {
+                return deserialize_key(self.parser, seed).map(Some);
+            }
+
+            deserialize_key(self.parser, PhantomData::<IgnoredAny>)?;
+            de::Deserialize::deserialize(&mut *self.parser).map(|IgnoredAny| ())?;
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value> {
+        seed.deserialize(&mut *self.parser)
+    }
+}
+
+/// Deserializes an object key and consumes the colon that follows it.
+fn deserialize_key<'de, K: DeserializeSeed<'de>>(
+    parser: &mut Parser<'de>,
+    seed: K,
+) -> Result<K::Value> {
+    let key = match parser.curr() {
+        Some(Token::String(range)) => parser.read_string(&range.clone())?,
+        Some(_) => return Err(parser.error(ParserErrorKind::InvalidKey).into()),
+        None => return Err(parser.error(ParserErrorKind::UnexpectedEof).into()),
+    };
+
+    let start = parser.pos;
+    parser.next()?;
+
+    if parser.curr() != Some(&Token::Colon) {
+        return Err(parser.error(ParserErrorKind::MissingColon).into());
+    }
+
+    parser.next()?;
+    seed.deserialize(Key(key))
+        .map_err(|e| parser.locate(e, start))
+}
+
+/// Deserializes an object key, which is always a string but may stand for
+/// a number in maps such as `HashMap<u32, T>`.
+struct Key<'de>(Cow<'de, str>);
+
+macro_rules! deserialize_parsed_key {
+    ($($method:ident => $visit:ident,)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+                match self.0.parse() {
+                    Ok(n) => visitor.$visit(n),
+                    Err(_) => self.deserialize_any(visitor),
+                }
+            }
+        )*
+    };
+}
+
+impl<'de> de::Deserializer<'de> for Key<'de> {
+    type Error = DeserializeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self.0 {
+            Cow::Borrowed(s) => visitor.visit_borrowed_str(s),
+            Cow::Owned(s) => visitor.visit_string(s),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    deserialize_parsed_key! {
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_i128 => visit_i128,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+        deserialize_u128 => visit_u128,
+        deserialize_bool => visit_bool,
+    }
+
+    forward_to_deserialize_any! {
+        f32 f64 char str string bytes byte_buf option unit unit_struct seq
+        tuple tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+struct Variant<'p, 'de> {
+    parser: &'p mut Parser<'de>,
+}
+
+impl<'de> EnumAccess<'de> for Variant<'_, 'de> {
+    type Error = DeserializeError;
+    type Variant = Self;
+
+    fn variant_seed<S: DeserializeSeed<'de>>(self, seed: S) -> Result<(S::Value, Self)> {
+        let variant = deserialize_key(self.parser, seed)?;
+        Ok((variant, self))
+    }
+}
+
+impl<'de> VariantAccess<'de> for Variant<'_, 'de> {
+    type Error = DeserializeError;
+
+    fn unit_variant(self) -> Result<()> {
+        de::Deserialize::deserialize(self.parser)
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value> {
+        seed.deserialize(self.parser)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value> {
+        de::Deserializer::deserialize_seq(self.parser, visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        de::Deserializer::deserialize_map(self.parser, visitor)
+    }
+}
+
+/// Deserializes a [`Value`] the way [`Parser::parse`] builds one: numbers
+/// are handed over as a [`NUMBER_TOKEN`] variant holding their exact text,
+/// and object members follow the duplicate key policy.
+struct ValueDeserializer<'p, 'de>(&'p mut Parser<'de>);
+
+impl<'de> de::Deserializer<'de> for ValueDeserializer<'_, 'de> {
+    type Error = DeserializeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        let parser = self.0;
+        let start = parser.pos;
+
+        let result = match parser.curr() {
+            Some(Token::Number(range)) => {
+                let number = parser.read_str(&range.clone())?;
+                parser.next()?;
+                visitor.visit_enum(Marked::Number(number))
+            }
+            Some(Token::Lcurl) => match parser.options.duplicate_keys {
+                DuplicateKeys::KeepAll => visitor.visit_enum(Marked::KeepAll(&mut *parser)),
+                policy => parser.deserialize_nested(visitor, Some(policy)),
+            },
+            _ => return de::Deserializer::deserialize_any(parser, visitor),
+        };
+
+        result.map_err(|e| parser.locate(e, start))
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+/// A value that [`ValueDeserializer`] hands over as an enum variant, so that
+/// [`ValueVisitor`] can tell it apart from anything in the input.
+enum Marked<'p, 'de> {
+    Number(&'de str),
+    /// An object whose members are all handed over, repeated keys included.
+    KeepAll(&'p mut Parser<'de>),
+}
+
+impl<'p, 'de> EnumAccess<'de> for Marked<'p, 'de> {
+    type Error = DeserializeError;
+    type Variant = Self;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Self)> {
+        let variant = match self {
+            Marked::Number(_) => NUMBER_TOKEN,
+            Marked::KeepAll(_) => KEEP_ALL_TOKEN,
+        };
+
+        let variant = BorrowedStrDeserializer::<DeserializeError>::new(variant);
+        Ok((seed.deserialize(variant)?, self))
+    }
+}
+
+impl<'de> VariantAccess<'de> for Marked<'_, 'de> {
+    type Error = DeserializeError;
+
+    fn unit_variant(self) -> Result<()> {
+        Err(de::Error::invalid_type(
+            Unexpected::NewtypeVariant,
+            &"unit variant",
+        ))
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value> {
+        match self {
+            Marked::Number(number) => seed.deserialize(BorrowedStrDeserializer::new(number)),
+            Marked::KeepAll(parser) => seed.deserialize(parser),
+        }
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _len: usize, _visitor: V) -> Result<V::Value> {
+        Err(de::Error::invalid_type(
+            Unexpected::NewtypeVariant,
+            &"tuple variant",
+        ))
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        _visitor: V,
+    ) -> Result<V::Value> {
+        Err(de::Error::invalid_type(
+            Unexpected::NewtypeVariant,
+            &"struct variant",
+        ))
+    }
+}
+
+/// Strings and numbers are always copied, so that an
+/// [`OwnedValue`](super::OwnedValue) can be deserialized from input that it
+/// outlives. Use [`borrow_value`] to share them with the input instead.
+///
+/// From this crate's deserializer, numbers keep their exact text and
+/// repeated keys follow the parser's duplicate key policy, just like
+/// [`Parser::parse`]. Other formats hand over integers and floats, and keep
+/// the last value of a repeated key at the position of the first.
+impl<'de> de::Deserialize<'de> for Value<'_> {
+    fn deserialize<D: de::Deserializer<'de>>(
+        deserializer: D,
+    ) -> std::result::Result<Self, D::Error> {
+        ValueSeed::<Copy>(PhantomData).deserialize(deserializer)
+    }
+}
+
+/// Deserializes a [`Value`] that borrows strings and numbers from the input
+/// whenever the format allows it, for fields such as
+///
+/// ```ignore
+/// #[serde(borrow, deserialize_with = "json_parser::parser::borrow_value")]
+/// value: Value<'a>,
+/// ```
+pub fn borrow_value<'de: 'a, 'a, D: de::Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<Value<'a>, D::Error> {
+    ValueSeed::<Borrow>(PhantomData).deserialize(deserializer)
+}
+
+/// How a [`Value`] stores the strings that a deserializer lends it.
+trait Lend<'de, 'a> {
+    fn lend(s: &'de str) -> Cow<'a, str>;
+}
+
+/// Keeps borrowing them.
+struct Borrow;
+
+/// Copies them.
+struct Copy;
+
+impl<'de: 'a, 'a> Lend<'de, 'a> for Borrow {
+    fn lend(s: &'de str) -> Cow<'a, str> {
+        Cow::Borrowed(s)
+    }
+}
+
+impl<'a> Lend<'_, 'a> for Copy {
+    fn lend(s: &str) -> Cow<'a, str> {
+        Cow::Owned(s.to_owned())
+    }
+}
+
+struct ValueSeed<'a, L>(PhantomData<(Value<'a>, L)>);
+
+impl<'de, 'a, L: Lend<'de, 'a>> DeserializeSeed<'de> for ValueSeed<'a, L> {
+    type Value = Value<'a>;
+
+    fn deserialize<D: de::Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> std::result::Result<Value<'a>, D::Error> {
+        deserializer.deserialize_newtype_struct(VALUE_TOKEN, ValueVisitor::<L>(PhantomData))
+    }
+}
+
+struct ValueVisitor<'a, L>(PhantomData<(Value<'a>, L)>);
+
+impl<'de, 'a, L: Lend<'de, 'a>> Visitor<'de> for ValueVisitor<'a, L> {
+    type Value = Value<'a>;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str("any json value")
+    }
+
+    fn visit_bool<E: de::Error>(self, v: bool) -> std::result::Result<Value<'a>, E> {
+        Ok(Value::Boolean(v))
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> std::result::Result<Value<'a>, E> {
+        Ok(Value::Number(v.into()))
+    }
+
+    fn visit_i128<E: de::Error>(self, v: i128) -> std::result::Result<Value<'a>, E> {
+        Ok(Value::Number(v.into()))
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> std::result::Result<Value<'a>, E> {
+        Ok(Value::Number(v.into()))
+    }
+
+    fn visit_u128<E: de::Error>(self, v: u128) -> std::result::Result<Value<'a>, E> {
+        Ok(Value::Number(v.into()))
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> std::result::Result<Value<'a>, E> {
+        Number::from_f64(v)
+            .map(Value::Number)
+            .ok_or_else(|| E::custom(format!("json cannot represent the number {v}")))
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> std::result::Result<Value<'a>, E> {
+        Ok(Value::String(Cow::Owned(v.to_owned())))
+    }
+
+    fn visit_borrowed_str<E: de::Error>(self, v: &'de str) -> std::result::Result<Value<'a>, E> {
+        Ok(Value::String(L::lend(v)))
+    }
+
+    fn visit_string<E: de::Error>(self, v: String) -> std::result::Result<Value<'a>, E> {
+        Ok(Value::String(Cow::Owned(v)))
+    }
+
+    fn visit_none<E: de::Error>(self) -> std::result::Result<Value<'a>, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_some<D: de::Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> std::result::Result<Value<'a>, D::Error> {
+        ValueSeed::<L>(PhantomData).deserialize(deserializer)
+    }
+
+    fn visit_unit<E: de::Error>(self) -> std::result::Result<Value<'a>, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_newtype_struct<D: de::Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> std::result::Result<Value<'a>, D::Error> {
+        deserializer.deserialize_any(self)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> std::result::Result<Value<'a>, A::Error> {
+        let mut array = Array::new();
+
+        while let Some(value) = seq.next_element_seed(ValueSeed::<L>(PhantomData))? {
+            array.push(value);
+        }
+
+        Ok(Value::Array(array))
+    }
+
+    /// Repeated keys keep the last value at the position of the first.
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> std::result::Result<Value<'a>, A::Error> {
+        visit_members::<L, A>(map, |object, key, value| {
+            object.insert(key, value);
+        })
+    }
+
+    /// Only [`ValueDeserializer`] sends enums, to mark exact numbers and
+    /// objects whose repeated keys must all be kept.
+    fn visit_enum<A: EnumAccess<'de>>(self, data: A) -> std::result::Result<Value<'a>, A::Error> {
+        let (variant, access) = data.variant_seed(StrSeed::<L>(PhantomData))?;
+
+        match &*variant {
+            NUMBER_TOKEN => {
+                let number = access.newtype_variant_seed(StrSeed::<L>(PhantomData))?;
+
+                match Number::parse(&number) {
+                    Some(_) => Ok(Value::Number(Number::from_raw(number))),
+                    None => Err(de::Error::custom(format!("invalid number {number}"))),
+                }
+            }
+            KEEP_ALL_TOKEN => access.newtype_variant_seed(KeepAll::<L>(PhantomData)),
+            _ => Err(de::Error::invalid_type(Unexpected::Enum, &self)),
+        }
+    }
+}
+
+/// Collects the members of `map` into an object, adding each with `add`.
+fn visit_members<'de, 'a, L: Lend<'de, 'a>, A: MapAccess<'de>>(
+    mut map: A,
+    add: fn(&mut Object<'a>, Cow<'a, str>, Value<'a>),
+) -> std::result::Result<Value<'a>, A::Error> {
+    let mut object = Object::new();
+
+    while let Some(key) = map.next_key_seed(StrSeed::<L>(PhantomData))? {
+        let value = map.next_value_seed(ValueSeed::<L>(PhantomData))?;
+        add(&mut object, key, value);
+    }
+
+    Ok(Value::Object(object))
+}
+
+/// Deserializes an object that keeps every member, repeated keys included.
+struct KeepAll<'a, L>(PhantomData<(Value<'a>, L)>);
+
+impl<'de, 'a, L: Lend<'de, 'a>> DeserializeSeed<'de> for KeepAll<'a, L> {
+    type Value = Value<'a>;
+
+    fn deserialize<D: de::Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> std::result::Result<Value<'a>, D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de, 'a, L: Lend<'de, 'a>> Visitor<'de> for KeepAll<'a, L> {
+    type Value = Value<'a>;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str("a json object")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> std::result::Result<Value<'a>, A::Error> {
+        visit_members::<L, A>(map, Object::push)
+    }
+}
+
+/// Deserializes an object key or the text of a number, which the
+/// `Deserialize` impl for `Cow` would never borrow.
+struct StrSeed<'a, L>(PhantomData<(Cow<'a, str>, L)>);
+
+impl<'de, 'a, L: Lend<'de, 'a>> DeserializeSeed<'de> for StrSeed<'a, L> {
+    type Value = Cow<'a, str>;
+
+    fn deserialize<D: de::Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> std::result::Result<Cow<'a, str>, D::Error> {
+        deserializer.deserialize_str(self)
+    }
+}
+
+impl<'de, 'a, L: Lend<'de, 'a>> Visitor<'de> for StrSeed<'a, L> {
+    type Value = Cow<'a, str>;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str("a string")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> std::result::Result<Cow<'a, str>, E> {
+        Ok(Cow::Owned(v.to_owned()))
+    }
+
+    fn visit_borrowed_str<E: de::Error>(self, v: &'de str) -> std::result::Result<Cow<'a, str>, E> {
+        Ok(L::lend(v))
+    }
+
+    fn visit_string<E: de::Error>(self, v: String) -> std::result::Result<Cow<'a, str>, E> {
+        Ok(Cow::Owned(v))
+    }
+}
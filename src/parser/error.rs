@@ -1,8 +1,56 @@
 use crate::lexer::Token;
 use thiserror::Error;
 
+#[derive(Debug)]
+pub struct ParserError {
+    pub kind: ParserErrorKind,
+    pub line: usize,
+    pub col: usize,
+    pub offset: usize,
+}
+
+impl ParserError {
+    /// Builds an error for the byte `offset` of `input`, deriving the line and
+    /// column the same way the lexer counts them.
+    pub fn new(kind: ParserErrorKind, input: &[u8], offset: usize) -> Self {
+        let offset = offset.min(input.len());
+        let (line, col) = line_col(input, offset);
+
+        Self {
+            kind,
+            line,
+            col,
+            offset,
+        }
+    }
+}
+
+/// Converts a byte offset into a 1-based line and column.
+pub(crate) fn line_col(input: &[u8], offset: usize) -> (usize, usize) {
+    let before = &input[..offset.min(input.len())];
+    let line = before.iter().filter(|&&b| b == b'\n').count() + 1;
+    let line_start = before
+        .iter()
+        .rposition(|&b| b == b'\n')
+        .map_or(0, |i| i + 1);
+
+    (line, before.len() - line_start + 1)
+}
+
+impl std::fmt::Display for ParserError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Error at line {}, col {}: {}",
+            self.line, self.col, self.kind
+        )
+    }
+}
+
+impl std::error::Error for ParserError {}
+
 #[derive(Debug, Error)]
-pub enum ParserError {
+pub enum ParserErrorKind {
     #[error(
         "Json values can only be an object, array, number, string, true, false, or null, found: {found:?}"
     )]
@@ -17,17 +65,32 @@ pub enum ParserError {
     #[error("Unexpected end of input")]
     UnexpectedEof,
 
+    #[error("Json documents must contain a value, found empty input")]
+    EmptyDocument,
+
     #[error("Keys must be strings")]
     InvalidKey,
 
-    #[error("Keys must be unique within an object")]
-    DuplicateKey,
+    #[error(
+        "Keys must be unique within an object, {key:?} was first defined at line {first_line}, col {first_col}"
+    )]
+    DuplicateKey {
+        key: String,
+        first_line: usize,
+        first_col: usize,
+    },
 
     #[error("Array value must either be terminated or followed by a comma")]
     InvalidArray,
 
     #[error("Trailing commas are not allowed")]
     TrailingComma,
+
+    #[error("Unexpected {found:?} after the end of the document")]
+    TrailingTokens { found: Token },
+
+    #[error("Objects and arrays cannot be nested more than {depth} levels deep")]
+    DepthLimitExceeded { depth: usize },
 }
 
 pub type Result<'a, T> = std::result::Result<T, ParserError>;
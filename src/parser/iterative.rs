@@ -0,0 +1,80 @@
+use std::borrow::Cow;
+use std::mem;
+
+use super::{Array, Object, Parser, Result, Value};
+use crate::lexer::Token;
+
+/// An object or array that has been opened but not yet closed.
+enum Frame<'a> {
+    Object(Object<'a>, Cow<'a, str>),
+    Array(Array<'a>),
+}
+
+impl<'a> Parser<'a> {
+    /// Parses a value like `parse_value`, but keeps open containers on a heap
+    /// allocated stack instead of recursing into them.
+    pub(super) fn parse_value_iterative(&mut self) -> Result<'a, Value<'a>> {
+        let mut stack: Vec<Frame<'a>> = Vec::new();
+
+        loop {
+            let mut value = match self.curr() {
+                Some(Token::Lcurl) => {
+                    self.check_depth(stack.len())?;
+                    self.next()?;
+
+                    if self.curr() == Some(&Token::Rcurl) {
+                        self.next()?;
+                        Value::Object(Object::new())
+                    } else {
+                        let object = Object::new();
+                        let key = self.parse_key(&object)?;
+                        stack.push(Frame::Object(object, key));
+                        continue;
+                    }
+                }
+                Some(Token::Lsquare) => {
+                    self.check_depth(stack.len())?;
+                    self.next()?;
+
+                    if self.curr() == Some(&Token::Rsquare) {
+                        self.next()?;
+                        Value::Array(Array::new())
+                    } else {
+                        stack.push(Frame::Array(Array::new()));
+                        continue;
+                    }
+                }
+                _ => self.parse_scalar()?,
+            };
+
+            // Hand the completed value to its parent, closing every container
+            // that it completes in turn.
+            loop {
+                match stack.last_mut() {
+                    None => return Ok(value),
+                    Some(Frame::Array(array)) => {
+                        array.push(value);
+
+                        if !self.parse_separator(&Token::Rsquare)? {
+                            break;
+                        }
+
+                        value = Value::Array(mem::take(array));
+                    }
+                    Some(Frame::Object(object, key)) => {
+                        self.insert_member(object, mem::take(key), value);
+
+                        if !self.parse_separator(&Token::Rcurl)? {
+                            *key = self.parse_key(object)?;
+                            break;
+                        }
+
+                        value = Value::Object(mem::take(object));
+                    }
+                }
+
+                stack.pop();
+            }
+        }
+    }
+}
@@ -0,0 +1,178 @@
+use std::{borrow::Cow, collections::HashSet, io};
+
+use thiserror::Error;
+
+use super::{DuplicateKeys, Parser, ParserError, ParserErrorKind};
+use crate::lexer::Token;
+
+#[derive(Debug, Error)]
+pub enum MinifyError {
+    #[error("{0}")]
+    Parser(#[from] ParserError),
+    #[error("{0}")]
+    Io(#[from] io::Error),
+    /// Dropping duplicate members would need the whole object in memory.
+    #[error(
+        "Minifying cannot drop duplicate keys, only the error and keep-all policies are supported"
+    )]
+    UnsupportedDuplicateKeys(DuplicateKeys),
+}
+
+/// An open container, holding the keys seen so far for objects whose
+/// duplicate keys are rejected.
+enum Frame<'a> {
+    Object(HashSet<Cow<'a, str>>),
+    Array,
+}
+
+impl<'a> Parser<'a> {
+    /// Writes the document to `writer` without any insignificant whitespace,
+    /// copying strings and numbers byte for byte from the input.
+    ///
+    /// The token stream is validated as it is written, but no `Value` is
+    /// built, so memory use beyond the tokens is limited to the keys of the
+    /// currently open objects, or nothing with [`DuplicateKeys::KeepAll`].
+    /// The policies that drop members are not supported. If an error is
+    /// found, everything before it has already been written.
+    pub fn minify<W: io::Write>(&mut self, writer: &mut W) -> Result<(), MinifyError> {
+        let duplicate_keys = self.options.duplicate_keys;
+
+        if matches!(
+            duplicate_keys,
+            DuplicateKeys::FirstWins | DuplicateKeys::LastWins
+        ) {
+            return Err(MinifyError::UnsupportedDuplicateKeys(duplicate_keys));
+        }
+
+        if self.curr().is_none() {
+            return Err(self.error(ParserErrorKind::EmptyDocument).into());
+        }
+
+        let mut stack: Vec<Frame<'a>> = Vec::new();
+
+        loop {
+            match self.curr() {
+                Some(Token::Lcurl) => {
+                    self.check_depth(stack.len())?;
+                    self.next()?;
+                    writer.write_all(b"{")?;
+
+                    if self.curr() == Some(&Token::Rcurl) {
+                        self.next()?;
+                        writer.write_all(b"}")?;
+                    } else {
+                        let mut keys = HashSet::new();
+                        self.minify_key(writer, &mut keys)?;
+                        stack.push(Frame::Object(keys));
+                        continue;
+                    }
+                }
+                Some(Token::Lsquare) => {
+                    self.check_depth(stack.len())?;
+                    self.next()?;
+                    writer.write_all(b"[")?;
+
+                    if self.curr() == Some(&Token::Rsquare) {
+                        self.next()?;
+                        writer.write_all(b"]")?;
+                    } else {
+                        stack.push(Frame::Array);
+                        continue;
+                    }
+                }
+                _ => self.minify_scalar(writer)?,
+            }
+
+            // Close every container that the completed value finishes.
+            loop {
+                let Some(frame) = stack.last_mut() else {
+                    self.check_end()?;
+                    return Ok(());
+                };
+
+                let (close, byte) = match frame {
+                    Frame::Object(_) => (Token::Rcurl, b"}"),
+                    Frame::Array => (Token::Rsquare, b"]"),
+                };
+
+                if !self.parse_separator(&close)? {
+                    writer.write_all(b",")?;
+
+                    if let Frame::Object(keys) = frame {
+                        self.minify_key(writer, keys)?;
+                    }
+
+                    break;
+                }
+
+                writer.write_all(byte)?;
+                stack.pop();
+            }
+        }
+    }
+
+    fn minify_scalar<W: io::Write>(&mut self, writer: &mut W) -> Result<(), MinifyError> {
+        let Some(token) = self.curr() else {
+            return Err(self.error(ParserErrorKind::UnexpectedEof).into());
+        };
+
+        match token {
+            Token::String(range) => {
+                let s = self.read_str(range)?;
+
+                writer.write_all(b"\"")?;
+                writer.write_all(s.as_bytes())?;
+                writer.write_all(b"\"")?;
+            }
+            Token::Number(range) => writer.write_all(&self.input[range.clone()])?,
+            Token::True => writer.write_all(b"true")?,
+            Token::False => writer.write_all(b"false")?,
+            Token::Null => writer.write_all(b"null")?,
+            _ => {
+                return Err(self
+                    .error(ParserErrorKind::InvalidValue {
+                        found: token.clone(),
+                    })
+                    .into());
+            }
+        }
+
+        self.next()?;
+        Ok(())
+    }
+
+    /// Writes an object key and its colon, recording it in `keys` unless
+    /// duplicates are allowed.
+    fn minify_key<W: io::Write>(
+        &mut self,
+        writer: &mut W,
+        keys: &mut HashSet<Cow<'a, str>>,
+    ) -> Result<(), MinifyError> {
+        match self.curr() {
+            Some(Token::String(range)) => {
+                if self.options.duplicate_keys == DuplicateKeys::Error {
+                    let key = self.read_string(range)?;
+
+                    if keys.contains(&key) {
+                        return Err(self.duplicate_key_error(key).into());
+                    }
+
+                    keys.insert(key);
+                }
+
+                self.minify_scalar(writer)?;
+            }
+            Some(_) => return Err(self.error(ParserErrorKind::InvalidKey).into()),
+            None => return Err(self.error(ParserErrorKind::UnexpectedEof).into()),
+        }
+
+        if self.curr() != Some(&Token::Colon) {
+            return Err(self.error(ParserErrorKind::MissingColon).into());
+        }
+
+        self.next()?;
+        writer.write_all(b":")?;
+
+        Ok(())
+    }
+}
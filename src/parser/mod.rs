@@ -1,133 +1,190 @@
+#[cfg(feature = "serde")]
+mod de;
 pub mod error;
+mod iterative;
+mod minify;
+pub mod number;
+pub mod object;
+mod unescape;
+mod value;
+
+#[cfg(feature = "serde")]
+pub use de::{DeserializeError, borrow_value, from_slice, from_str};
+pub use error::{ParserError, ParserErrorKind, Result};
+pub use minify::MinifyError;
+pub use number::{Decimal, Number, NumberConversionError};
+pub use object::Object;
+pub use value::{Array, OwnedValue, Value, ValueKind};
+
+use std::{borrow::Cow, cell::OnceCell, ops::Range};
+
+use crate::lexer::{Lexer, Token};
+
+/// Default limit on how deeply objects and arrays may be nested.
+pub const DEFAULT_MAX_DEPTH: usize = 128;
+
+/// How to handle an object that contains the same key more than once.
+///
+/// RFC 8259 only says that names SHOULD be unique, so real-world documents
+/// sometimes rely on one of the more lenient behaviours.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DuplicateKeys {
+    /// Reject the document with [`ParserErrorKind::DuplicateKey`].
+    #[default]
+    Error,
+    /// Keep the value of the first occurrence and ignore later ones.
+    FirstWins,
+    /// Keep the value of the last occurrence, at the position of the first.
+    LastWins,
+    /// Keep every occurrence, see [`Object::get_all`].
+    KeepAll,
+}
 
-pub use error::{ParserError, Result};
-
-use std::{collections::HashMap, ops::Range};
-
-use crate::lexer::Token;
+impl std::str::FromStr for DuplicateKeys {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "error" => Ok(Self::Error),
+            "first-wins" => Ok(Self::FirstWins),
+            "last-wins" => Ok(Self::LastWins),
+            "keep-all" => Ok(Self::KeepAll),
+            _ => Err(format!(
+                "unknown duplicate key policy '{s}', expected one of: error, first-wins, last-wins, keep-all"
+            )),
+        }
+    }
+}
 
-#[allow(unused)]
 #[derive(Clone, Debug)]
-pub enum Value<'a> {
-    Object(HashMap<&'a str, Value<'a>>),
-    Array(Vec<Value<'a>>),
-    String(&'a str),
-    Number(&'a str),
-    Boolean(bool),
-    Null,
+pub struct ParserOptions {
+    /// Stop after the first complete value instead of rejecting any tokens
+    /// that follow it, so that a prefix of the token stream can be parsed.
+    pub allow_trailing_tokens: bool,
+    /// Maximum number of nested objects and arrays, or `None` for no limit.
+    /// The recursive parser uses one stack frame per level, so an unbounded
+    /// depth lets untrusted input overflow the stack unless `iterative` is set.
+    pub max_depth: Option<usize>,
+    /// Parse with an explicit stack on the heap instead of recursion, so
+    /// that depth is limited only by available memory.
+    pub iterative: bool,
+    pub duplicate_keys: DuplicateKeys,
+}
+
+impl Default for ParserOptions {
+    fn default() -> Self {
+        Self {
+            allow_trailing_tokens: false,
+            max_depth: Some(DEFAULT_MAX_DEPTH),
+            iterative: false,
+            duplicate_keys: DuplicateKeys::default(),
+        }
+    }
 }
 
 pub struct Parser<'a> {
     input: &'a [u8],
     tokens: Vec<Token>,
     pos: usize,
+    depth: usize,
+    options: ParserOptions,
+    /// The byte range of each token, used to locate errors. Either given by
+    /// the caller or produced by lexing the input again on the first error.
+    spans: OnceCell<Vec<Range<usize>>>,
 }
 
 impl<'a> Parser<'a> {
     pub fn new(tokens: Vec<Token>, input: &'a [u8]) -> Self {
+        Self::with_options(tokens, input, ParserOptions::default())
+    }
+
+    pub fn with_options(tokens: Vec<Token>, input: &'a [u8], options: ParserOptions) -> Self {
         Self {
             input,
             tokens,
             pos: 0,
+            depth: 0,
+            options,
+            spans: OnceCell::new(),
         }
     }
 
-    pub fn parse(&mut self) -> Option<std::result::Result<Value<'a>, ParserError>> {
-        self.curr()?;
+    /// Like [`Parser::with_options`], but takes the token spans returned by
+    /// [`Lexer::lex_with_spans`] so that errors can be located without
+    /// lexing the input again.
+    pub fn with_spans(
+        tokens: Vec<Token>,
+        spans: Vec<Range<usize>>,
+        input: &'a [u8],
+        options: ParserOptions,
+    ) -> Self {
+        debug_assert_eq!(tokens.len(), spans.len());
 
-        Some(self.parse_value())
+        Self {
+            spans: OnceCell::from(spans),
+            ..Self::with_options(tokens, input, options)
+        }
     }
 
-    fn parse_value(&mut self) -> std::result::Result<Value<'a>, ParserError> {
-        let Some(token) = self.curr() else {
-            return Err(ParserError::UnexpectedEof);
+    pub fn parse(&mut self) -> Result<'a, Value<'a>> {
+        if self.curr().is_none() {
+            return Err(self.error(ParserErrorKind::EmptyDocument));
+        }
+
+        let value = if self.options.iterative {
+            self.parse_value_iterative()?
+        } else {
+            self.parse_value()?
         };
 
-        match token {
-            Token::Lcurl => self.parse_object(),
-            Token::Lsquare => self.parse_array(),
-            Token::String(range) => {
-                let s = Self::read_str(self.input, range)?;
-                self.next()?;
-                Ok(Value::String(s))
-            }
-            Token::Number(range) => {
-                let s = Self::read_str(self.input, range)?;
-                self.next()?;
-                Ok(Value::Number(s))
-            }
-            Token::True => {
-                self.next()?;
-                Ok(Value::Boolean(true))
-            }
-            Token::False => {
-                self.next()?;
-                Ok(Value::Boolean(false))
-            }
-            Token::Null => {
-                self.next()?;
-                Ok(Value::Null)
-            }
-            _ => Err(ParserError::InvalidValue {
+        self.check_end()?;
+        Ok(value)
+    }
+
+    /// Rejects any tokens left after the top-level value, unless trailing
+    /// tokens are allowed.
+    fn check_end(&self) -> Result<'a, ()> {
+        if !self.options.allow_trailing_tokens
+            && let Some(token) = self.curr()
+        {
+            return Err(self.error(ParserErrorKind::TrailingTokens {
                 found: token.clone(),
-            }),
+            }));
+        }
+
+        Ok(())
+    }
+
+    fn parse_value(&mut self) -> Result<'a, Value<'a>> {
+        match self.curr() {
+            Some(Token::Lcurl) => self.parse_nested(Self::parse_object),
+            Some(Token::Lsquare) => self.parse_nested(Self::parse_array),
+            _ => self.parse_scalar(),
         }
     }
 
     fn parse_object(&mut self) -> Result<'a, Value<'a>> {
-        let mut object: HashMap<&str, Value<'a>> = HashMap::new();
+        let mut object = Object::new();
         self.next()?;
 
-        while let Some(token) = self.curr() {
-            if token == &Token::Rcurl {
-                self.next()?;
-                return Ok(Value::Object(object));
-            }
-
-            let Some(Token::String(range)) = self.curr() else {
-                return Err(ParserError::InvalidKey);
-            };
-
-            let key = Self::read_str(self.input, range)?;
-
-            if object.contains_key(&key) {
-                return Err(ParserError::DuplicateKey);
-            };
-
+        if self.curr() == Some(&Token::Rcurl) {
             self.next()?;
+            return Ok(Value::Object(object));
+        }
 
-            let Ok(Token::Colon) = self.next() else {
-                return Err(ParserError::MissingColon);
-            };
-
+        loop {
+            let key = self.parse_key(&object)?;
             let value = self.parse_value()?;
-            object.insert(key, value);
-
-            match self.curr() {
-                Some(Token::Comma) => {
-                    if self.peek() == Some(&Token::Rcurl) {
-                        return Err(ParserError::TrailingComma);
-                    }
+            self.insert_member(&mut object, key, value);
 
-                    self.next()?;
-                }
-                Some(Token::Rcurl) => {
-                    self.next()?;
-                    return Ok(Value::Object(object));
-                }
-                other => {
-                    return Err(ParserError::InvalidValue {
-                        found: other.cloned().unwrap_or(Token::Null),
-                    });
-                }
+            if self.parse_separator(&Token::Rcurl)? {
+                return Ok(Value::Object(object));
             }
         }
-
-        Err(ParserError::UnexpectedEof)
     }
 
     fn parse_array(&mut self) -> Result<'a, Value<'a>> {
-        let mut array = Vec::new();
+        let mut array = Array::new();
         self.next()?;
 
         if self.curr() == Some(&Token::Rsquare) {
@@ -135,44 +192,161 @@ impl<'a> Parser<'a> {
             return Ok(Value::Array(array));
         }
 
-        while let Some(token) = self.curr() {
-            if token == &Token::Rsquare {
-                self.next()?;
+        loop {
+            let value = self.parse_value()?;
+            array.push(value);
+
+            if self.parse_separator(&Token::Rsquare)? {
                 return Ok(Value::Array(array));
             }
+        }
+    }
 
-            let value = self.parse_value()?;
-            array.push(value);
+    fn parse_nested(
+        &mut self,
+        parse: fn(&mut Self) -> Result<'a, Value<'a>>,
+    ) -> Result<'a, Value<'a>> {
+        self.check_depth(self.depth)?;
 
-            match self.curr() {
-                Some(Token::Comma) => {
-                    self.next()?;
-                }
-                Some(Token::Rsquare) => {
-                    self.next()?;
-                    return Ok(Value::Array(array));
+        self.depth += 1;
+        let value = parse(self);
+        self.depth -= 1;
+
+        value
+    }
+
+    /// Parses any value other than an object or array.
+    fn parse_scalar(&mut self) -> Result<'a, Value<'a>> {
+        let Some(token) = self.curr() else {
+            return Err(self.error(ParserErrorKind::UnexpectedEof));
+        };
+
+        let value = match token {
+            Token::String(range) => Value::String(self.read_string(range)?),
+            Token::Number(range) => Value::Number(Number::from_raw(self.read_str(range)?)),
+            Token::True => Value::Boolean(true),
+            Token::False => Value::Boolean(false),
+            Token::Null => Value::Null,
+            _ => {
+                return Err(self.error(ParserErrorKind::InvalidValue {
+                    found: token.clone(),
+                }));
+            }
+        };
+
+        self.next()?;
+        Ok(value)
+    }
+
+    /// Parses an object key and the colon that follows it.
+    fn parse_key(&mut self, object: &Object<'a>) -> Result<'a, Cow<'a, str>> {
+        let key = match self.curr() {
+            Some(Token::String(range)) => self.read_string(range)?,
+            Some(_) => return Err(self.error(ParserErrorKind::InvalidKey)),
+            None => return Err(self.error(ParserErrorKind::UnexpectedEof)),
+        };
+
+        if self.options.duplicate_keys == DuplicateKeys::Error && object.contains_key(&key) {
+            return Err(self.duplicate_key_error(key));
+        }
+
+        self.next()?;
+
+        if self.curr() != Some(&Token::Colon) {
+            return Err(self.error(ParserErrorKind::MissingColon));
+        }
+
+        self.next()?;
+        Ok(key)
+    }
+
+    fn insert_member(&self, object: &mut Object<'a>, key: Cow<'a, str>, value: Value<'a>) {
+        match self.options.duplicate_keys {
+            // Duplicates were already rejected by `parse_key`.
+            DuplicateKeys::Error | DuplicateKeys::KeepAll => object.push(key, value),
+            DuplicateKeys::FirstWins => {
+                if !object.contains_key(&key) {
+                    object.push(key, value);
                 }
-                _ => {
-                    return Err(ParserError::InvalidArray);
+            }
+            DuplicateKeys::LastWins => {
+                object.insert(key, value);
+            }
+        }
+    }
+
+    /// Builds the error for a repeated `key` at the current token, locating
+    /// its first occurrence by walking back through the enclosing object.
+    fn duplicate_key_error(&self, key: Cow<'a, str>) -> ParserError {
+        let mut depth = 0usize;
+        let mut first = self.pos;
+
+        for i in (0..self.pos).rev() {
+            match &self.tokens[i] {
+                Token::Rcurl | Token::Rsquare => depth += 1,
+                Token::Lcurl | Token::Lsquare if depth == 0 => break,
+                Token::Lcurl | Token::Lsquare => depth -= 1,
+                Token::String(range)
+                    if depth == 0
+                        && self.tokens.get(i + 1) == Some(&Token::Colon)
+                        && self.read_string(range).is_ok_and(|k| k == key) =>
+                {
+                    first = i;
                 }
+                _ => {}
             }
         }
 
-        Err(ParserError::UnexpectedEof)
+        let (first_line, first_col) = error::line_col(self.input, self.offset_of(first));
+
+        self.error(ParserErrorKind::DuplicateKey {
+            key: key.into_owned(),
+            first_line,
+            first_col,
+        })
+    }
+
+    /// Consumes the comma or closing token after an object member or array
+    /// element, returning whether the container was closed.
+    fn parse_separator(&mut self, close: &Token) -> Result<'a, bool> {
+        match self.curr() {
+            Some(Token::Comma) => {
+                if self.peek() == Some(close) {
+                    return Err(self.error(ParserErrorKind::TrailingComma));
+                }
+
+                self.next()?;
+                Ok(false)
+            }
+            Some(token) if token == close => {
+                self.next()?;
+                Ok(true)
+            }
+            _ if close == &Token::Rsquare => Err(self.error(ParserErrorKind::InvalidArray)),
+            Some(other) => Err(self.error(ParserErrorKind::InvalidValue {
+                found: other.clone(),
+            })),
+            None => Err(self.error(ParserErrorKind::UnexpectedEof)),
+        }
     }
 
-    fn next(&mut self) -> std::result::Result<&Token, ParserError> {
-        if self.curr().is_some() {
-            let token = self
-                .tokens
-                .get(self.pos)
-                .ok_or(ParserError::UnexpectedEof)?;
+    fn check_depth(&self, depth: usize) -> Result<'a, ()> {
+        match self.options.max_depth {
+            Some(max_depth) if depth >= max_depth => {
+                Err(self.error(ParserErrorKind::DepthLimitExceeded { depth: max_depth }))
+            }
+            _ => Ok(()),
+        }
+    }
 
+    fn next(&mut self) -> Result<'a, &Token> {
+        if self.pos < self.tokens.len() {
+            let token = &self.tokens[self.pos];
             self.pos += 1;
 
             Ok(token)
         } else {
-            Err(ParserError::UnexpectedEof)
+            Err(self.error(ParserErrorKind::UnexpectedEof))
         }
     }
 
@@ -184,13 +358,88 @@ impl<'a> Parser<'a> {
         self.tokens.get(self.pos + 1)
     }
 
-    fn read_str<'b>(
-        input: &'b [u8],
-        range: &Range<usize>,
-    ) -> std::result::Result<&'b str, ParserError> {
-        std::str::from_utf8(&input[range.clone()]).map_err(|_| ParserError::NonUTF8String)
+    /// Builds an error located at the current token.
+    fn error(&self, kind: ParserErrorKind) -> ParserError {
+        ParserError::new(kind, self.input, self.offset_of(self.pos))
+    }
+
+    /// Returns the byte offset of the token at `index`, or the end of the
+    /// input if there is no such token.
+    fn offset_of(&self, index: usize) -> usize {
+        let spans = self.spans.get_or_init(|| {
+            Lexer::new(self.input)
+                .lex_with_spans()
+                .map(|(_, spans)| spans)
+                .unwrap_or_default()
+        });
+
+        spans.get(index).map_or(self.input.len(), |span| span.start)
+    }
+
+    fn read_string(&self, range: &Range<usize>) -> Result<'a, Cow<'a, str>> {
+        let s = self.read_str(range)?;
+
+        if s.contains('\\') {
+            Ok(Cow::Owned(unescape::unescape(s)))
+        } else {
+            Ok(Cow::Borrowed(s))
+        }
+    }
+
+    fn read_str(&self, range: &Range<usize>) -> Result<'a, &'a str> {
+        std::str::from_utf8(&self.input[range.clone()])
+            .map_err(|_| self.error(ParserErrorKind::NonUTF8String))
     }
 }
 
+/// Lexes and parses `input` for tests elsewhere in the crate, panicking if
+/// it is not valid json.
+#[cfg(test)]
+pub(crate) fn parse(input: &str) -> Value<'_> {
+    parse_with(input, ParserOptions::default())
+}
+
+/// Like [`parse`], with the given options.
+#[cfg(test)]
+pub(crate) fn parse_with(input: &str, options: ParserOptions) -> Value<'_> {
+    let bytes = input.as_bytes();
+    let tokens = crate::lexer::Lexer::new(bytes)
+        .lex()
+        .expect("Lexing failed");
+
+    Parser::with_options(tokens, bytes, options)
+        .parse()
+        .expect("Parsing failed")
+}
+
+/// How many levels [`parse_deep`] nests, far beyond what recursion over the
+/// document could survive.
+#[cfg(test)]
+pub(crate) const DEEP: usize = 100_000;
+
+/// Parses `open` repeated [`DEEP`] times around `innermost`, followed by the
+/// brackets that close it, using the iterative parser. The input is leaked
+/// so that the value can keep borrowing from it.
+#[cfg(test)]
+pub(crate) fn parse_deep(open: &str, innermost: &str) -> Value<'static> {
+    let close: String = open
+        .chars()
+        .rev()
+        .filter_map(|c| match c {
+            '[' => Some(']'),
+            '{' => Some('}'),
+            _ => None,
+        })
+        .collect();
+    let input = format!("{}{innermost}{}", open.repeat(DEEP), close.repeat(DEEP));
+    let options = ParserOptions {
+        iterative: true,
+        max_depth: None,
+        ..Default::default()
+    };
+
+    parse_with(String::leak(input), options)
+}
+
 #[cfg(test)]
 mod test;
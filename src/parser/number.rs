@@ -0,0 +1,353 @@
+use std::{borrow::Cow, cmp::Ordering, fmt, str::FromStr};
+
+use thiserror::Error;
+
+use crate::lexer::{Lexer, Token};
+
+/// A json number, kept as the exact text it was written with so that no
+/// precision is lost until it is converted with one of the accessors.
+#[derive(Clone, Debug)]
+pub struct Number<'a>(Cow<'a, str>);
+
+/// The name a [`Number`] goes by when it passes through serde as its exact
+/// text, which only this crate's serializer and deserializer use.
+#[cfg(feature = "serde")]
+pub(crate) const NUMBER_TOKEN: &str = "$json_parser::private::Number";
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum NumberConversionError {
+    #[error("number has a fractional part")]
+    Fractional,
+    #[error("number is out of range for the target type")]
+    OutOfRange,
+    #[error("number cannot be represented exactly as a 64-bit float")]
+    PrecisionLoss,
+}
+
+impl<'a> Number<'a> {
+    /// Wraps text that the lexer has already validated as a json number.
+    pub(crate) fn from_raw(raw: impl Into<Cow<'a, str>>) -> Self {
+        Self(raw.into())
+    }
+
+    /// Returns `None` unless `s` is a valid json number.
+    pub fn parse(s: &'a str) -> Option<Self> {
+        match Lexer::new(s.as_bytes()).lex().as_deref() {
+            Ok([Token::Number(range)]) if *range == (0..s.len()) => Some(Self::from_raw(s)),
+            _ => None,
+        }
+    }
+
+    /// Returns `None` for NaN and infinities, which json cannot represent.
+    pub fn from_f64(f: f64) -> Option<Number<'static>> {
+        f.is_finite().then(|| Number(Cow::Owned(format!("{f:?}"))))
+    }
+
+    /// Like [`Number::from_f64`], but uses the shortest text that reads back
+    /// as the same `f32`.
+    pub fn from_f32(f: f32) -> Option<Number<'static>> {
+        f.is_finite().then(|| Number(Cow::Owned(format!("{f:?}"))))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn into_owned(self) -> Number<'static> {
+        Number(Cow::Owned(self.0.into_owned()))
+    }
+
+    /// Whether the number has no fractional part, so `1.0` and `2e3` count.
+    pub fn is_integer(&self) -> bool {
+        self.as_decimal().exponent >= 0
+    }
+
+    pub fn as_i64(&self) -> Result<i64, NumberConversionError> {
+        if let Ok(n) = self.0.parse() {
+            return Ok(n);
+        }
+
+        self.as_i128()?
+            .try_into()
+            .map_err(|_| NumberConversionError::OutOfRange)
+    }
+
+    pub fn as_u64(&self) -> Result<u64, NumberConversionError> {
+        if let Ok(n) = self.0.parse() {
+            return Ok(n);
+        }
+
+        self.as_i128()?
+            .try_into()
+            .map_err(|_| NumberConversionError::OutOfRange)
+    }
+
+    pub fn as_i128(&self) -> Result<i128, NumberConversionError> {
+        if let Ok(n) = self.0.parse() {
+            return Ok(n);
+        }
+
+        self.as_decimal().to_i128()
+    }
+
+    /// Converts to the nearest `f64`, failing if that float does not convert
+    /// back to the same decimal value.
+    pub fn as_f64(&self) -> Result<f64, NumberConversionError> {
+        let f = self.as_f64_lossy();
+
+        if f.is_infinite() {
+            return Err(NumberConversionError::OutOfRange);
+        }
+
+        let round_trip: Decimal = format!("{f:e}").parse().expect("floats format as decimals");
+
+        if round_trip == self.as_decimal() {
+            Ok(f)
+        } else {
+            Err(NumberConversionError::PrecisionLoss)
+        }
+    }
+
+    /// Converts to the nearest `f64`, which is infinite if the number is too
+    /// large in magnitude.
+    pub fn as_f64_lossy(&self) -> f64 {
+        self.0.parse().unwrap_or(f64::NAN)
+    }
+
+    /// Returns the exact arbitrary-precision value of the number.
+    pub fn as_decimal(&self) -> Decimal {
+        self.0.parse().expect("numbers are valid decimals")
+    }
+}
+
+/// Numbers are equal when they have the same value, so `1`, `1.0` and `10e-1`
+/// are all equal.
+impl PartialEq for Number<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0 || self.as_decimal() == other.as_decimal()
+    }
+}
+
+impl Eq for Number<'_> {}
+
+impl PartialOrd for Number<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Number<'_> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_decimal().cmp(&other.as_decimal())
+    }
+}
+
+impl fmt::Display for Number<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+macro_rules! impl_from_integer {
+    ($($ty:ty),*) => {
+        $(
+            impl From<$ty> for Number<'static> {
+                fn from(n: $ty) -> Self {
+                    Number(Cow::Owned(n.to_string()))
+                }
+            }
+        )*
+    };
+}
+
+impl_from_integer!(
+    i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize
+);
+
+impl From<Decimal> for Number<'static> {
+    fn from(decimal: Decimal) -> Self {
+        Number(Cow::Owned(decimal.to_string()))
+    }
+}
+
+/// An arbitrary-precision decimal, `digits * 10^exponent`.
+///
+/// Unlike the text of a [`Number`] it is normalized, so two decimals with the
+/// same value compare and print identically.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Decimal {
+    negative: bool,
+    /// Significant digits without leading or trailing zeros, empty for zero.
+    digits: String,
+    exponent: i64,
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+#[error("invalid decimal number")]
+pub struct ParseDecimalError;
+
+impl Decimal {
+    pub fn is_zero(&self) -> bool {
+        self.digits.is_empty()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    pub fn to_i128(&self) -> Result<i128, NumberConversionError> {
+        if self.exponent < 0 {
+            return Err(NumberConversionError::Fractional);
+        }
+
+        if self.digits.len() as i64 + self.exponent > 39 {
+            return Err(NumberConversionError::OutOfRange);
+        }
+
+        let zeros = std::iter::repeat_n(0, self.exponent as usize);
+        let mut digits = self.digits.bytes().map(|b| (b - b'0') as i128).chain(zeros);
+
+        // Accumulating towards the sign of the result lets i128::MIN through.
+        digits
+            .try_fold(0i128, |acc, digit| {
+                let acc = acc.checked_mul(10)?;
+
+                if self.negative {
+                    acc.checked_sub(digit)
+                } else {
+                    acc.checked_add(digit)
+                }
+            })
+            .ok_or(NumberConversionError::OutOfRange)
+    }
+
+    /// Position of the decimal point relative to the start of `digits`.
+    fn point(&self) -> i64 {
+        self.digits.len() as i64 + self.exponent
+    }
+}
+
+impl FromStr for Decimal {
+    type Err = ParseDecimalError;
+
+    /// Parses json number syntax, also accepting leading zeros and a leading `+`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (negative, s) = match s.as_bytes().first() {
+            Some(b'-') => (true, &s[1..]),
+            Some(b'+') => (false, &s[1..]),
+            _ => (false, s),
+        };
+
+        let (mantissa, exponent) = match s.find(['e', 'E']) {
+            Some(i) => (&s[..i], Some(&s[i + 1..])),
+            None => (s, None),
+        };
+
+        let (int, frac) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+        let is_digits = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+
+        if int.is_empty() || !is_digits(int) || !is_digits(frac) || mantissa.ends_with('.') {
+            return Err(ParseDecimalError);
+        }
+
+        let exponent = match exponent {
+            None => 0,
+            Some(exp) => {
+                let (sign, digits) = match exp.as_bytes().first() {
+                    Some(b'-') => (-1, &exp[1..]),
+                    Some(b'+') => (1, &exp[1..]),
+                    _ => (1, exp),
+                };
+
+                if digits.is_empty() || !is_digits(digits) {
+                    return Err(ParseDecimalError);
+                }
+
+                // Exponents this large only ever mean overflow or zero, so
+                // saturating keeps the arithmetic below from overflowing.
+                let magnitude = digits.bytes().fold(0i64, |acc, b| {
+                    acc.saturating_mul(10)
+                        .saturating_add((b - b'0') as i64)
+                        .min(i64::MAX / 4)
+                });
+
+                sign * magnitude
+            }
+        };
+
+        let digits = format!("{int}{frac}");
+        let trimmed = digits.trim_start_matches('0');
+        let significant = trimmed.trim_end_matches('0');
+
+        if significant.is_empty() {
+            return Ok(Self {
+                negative: false,
+                digits: String::new(),
+                exponent: 0,
+            });
+        }
+
+        Ok(Self {
+            negative,
+            digits: significant.to_string(),
+            exponent: exponent - frac.len() as i64 + (trimmed.len() - significant.len()) as i64,
+        })
+    }
+}
+
+impl fmt::Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_zero() {
+            return f.write_str("0");
+        }
+
+        if self.negative {
+            f.write_str("-")?;
+        }
+
+        let point = self.point();
+
+        if self.exponent >= 0 && point <= 21 {
+            write!(f, "{}{}", self.digits, "0".repeat(self.exponent as usize))
+        } else if self.exponent < 0 && point > 0 {
+            let (int, frac) = self.digits.split_at(point as usize);
+            write!(f, "{int}.{frac}")
+        } else if self.exponent < 0 && point > -6 {
+            write!(f, "0.{}{}", "0".repeat(-point as usize), self.digits)
+        } else {
+            let (first, rest) = self.digits.split_at(1);
+            let dot = if rest.is_empty() { "" } else { "." };
+            write!(f, "{first}{dot}{rest}e{}", point - 1)
+        }
+    }
+}
+
+impl PartialOrd for Decimal {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Decimal {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let sign = |d: &Self| match (d.is_zero(), d.negative) {
+            (true, _) => 0,
+            (false, true) => -1,
+            (false, false) => 1,
+        };
+
+        let magnitude = || {
+            // Digits have no leading or trailing zeros, so with equal point
+            // positions comparing them as strings compares their values.
+            self.point()
+                .cmp(&other.point())
+                .then_with(|| self.digits.cmp(&other.digits))
+        };
+
+        match sign(self).cmp(&sign(other)) {
+            Ordering::Equal if self.negative => magnitude().reverse(),
+            Ordering::Equal => magnitude(),
+            ordering => ordering,
+        }
+    }
+}
@@ -0,0 +1,321 @@
+use std::{borrow::Cow, collections::HashMap, mem, slice, vec};
+
+use super::value::{Value, drop_iteratively};
+
+/// Objects with more members than this get a hash index for lookups; smaller
+/// ones are scanned linearly, which is faster and saves memory.
+const INDEX_THRESHOLD: usize = 16;
+
+/// The members of a json object, kept in the order they appear in the input.
+///
+/// Like [`Array`](super::Array), an object is dropped without recursion.
+#[derive(Clone, Debug, Default)]
+pub struct Object<'a> {
+    entries: Vec<(Cow<'a, str>, Value<'a>)>,
+    /// Maps each key to the position of its first entry. Left empty while
+    /// the object is at most `INDEX_THRESHOLD` members long.
+    index: HashMap<Cow<'a, str>, usize>,
+}
+
+impl<'a> Object<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the value of the first member named `key`.
+    pub fn get(&self, key: &str) -> Option<&Value<'a>> {
+        self.position(key).map(|i| &self.entries[i].1)
+    }
+
+    /// Returns the values of every member named `key`, which can be more than
+    /// one when parsing with [`DuplicateKeys::KeepAll`](super::DuplicateKeys).
+    pub fn get_all<'b>(&'b self, key: &'b str) -> impl Iterator<Item = &'b Value<'a>> {
+        self.entries
+            .iter()
+            .filter(move |(k, _)| k == key)
+            .map(|(_, value)| value)
+    }
+
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut Value<'a>> {
+        self.position(key).map(|i| &mut self.entries[i].1)
+    }
+
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.position(key).is_some()
+    }
+
+    /// Returns the first member named `key` along with the key as stored.
+    pub fn get_key_value(&self, key: &str) -> Option<(&str, &Value<'a>)> {
+        self.position(key).and_then(|i| self.get_index(i))
+    }
+
+    /// Returns the member at `index` in insertion order.
+    pub fn get_index(&self, index: usize) -> Option<(&str, &Value<'a>)> {
+        self.entries
+            .get(index)
+            .map(|(key, value)| (key.as_ref(), value))
+    }
+
+    /// Sets the value for `key`, keeping its position if it is already
+    /// present and appending it otherwise. Returns the previous value.
+    pub fn insert(&mut self, key: impl Into<Cow<'a, str>>, value: Value<'a>) -> Option<Value<'a>> {
+        let key = key.into();
+
+        match self.position(&key) {
+            Some(i) => Some(mem::replace(&mut self.entries[i].1, value)),
+            None => {
+                self.push(key, value);
+                None
+            }
+        }
+    }
+
+    /// Removes `key`, shifting later members down to preserve their order.
+    pub fn remove(&mut self, key: &str) -> Option<Value<'a>> {
+        let i = self.position(key)?;
+        let (_, value) = self.entries.remove(i);
+        self.rebuild_index();
+
+        Some(value)
+    }
+
+    pub fn iter(&self) -> Iter<'_, 'a> {
+        Iter(self.entries.iter())
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, 'a> {
+        IterMut(self.entries.iter_mut())
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.entries.iter().map(|(key, _)| key.as_ref())
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &Value<'a>> {
+        self.entries.iter().map(|(_, value)| value)
+    }
+
+    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut Value<'a>> {
+        self.entries.iter_mut().map(|(_, value)| value)
+    }
+
+    /// See [`Value::into_owned`].
+    pub fn into_owned(self) -> Object<'static> {
+        match Value::Object(self).into_owned() {
+            Value::Object(object) => object,
+            _ => unreachable!("objects stay objects"),
+        }
+    }
+
+    /// Appends a member without checking whether `key` is already present.
+    pub(super) fn push(&mut self, key: Cow<'a, str>, value: Value<'a>) {
+        if !self.index.is_empty() {
+            self.index.entry(key.clone()).or_insert(self.entries.len());
+        }
+
+        self.entries.push((key, value));
+
+        if self.index.is_empty() && self.entries.len() > INDEX_THRESHOLD {
+            self.rebuild_index();
+        }
+    }
+
+    fn position(&self, key: &str) -> Option<usize> {
+        if self.index.is_empty() {
+            self.entries.iter().position(|(k, _)| k == key)
+        } else {
+            self.index.get(key).copied()
+        }
+    }
+
+    /// Pairs each member with the member of `other` it has to equal, pushing
+    /// the pairs onto `pairs`. Returns false if the keys already differ.
+    ///
+    /// Members are matched by key regardless of order, and repeated keys
+    /// must appear as often in both objects, matched in the order they
+    /// appear.
+    pub(super) fn pair_members<'b>(
+        &'b self,
+        other: &'b Self,
+        pairs: &mut Vec<(&'b Value<'a>, &'b Value<'a>)>,
+    ) -> bool {
+        if self.len() != other.len() {
+            return false;
+        }
+
+        if self.has_unique_keys() && other.has_unique_keys() {
+            for (key, value) in self {
+                match other.get(key) {
+                    Some(other) => pairs.push((value, other)),
+                    None => return false,
+                }
+            }
+
+            return true;
+        }
+
+        for (i, (key, _)) in self.iter().enumerate() {
+            // Every member with this key was paired at its first occurrence.
+            if self.position(key) != Some(i) {
+                continue;
+            }
+
+            if self.get_all(key).count() != other.get_all(key).count() {
+                return false;
+            }
+
+            pairs.extend(self.get_all(key).zip(other.get_all(key)));
+        }
+
+        true
+    }
+
+    pub(super) fn entries(&self) -> slice::Iter<'_, (Cow<'a, str>, Value<'a>)> {
+        self.entries.iter()
+    }
+
+    fn has_unique_keys(&self) -> bool {
+        if self.index.is_empty() {
+            self.entries
+                .iter()
+                .enumerate()
+                .all(|(i, (key, _))| self.position(key) == Some(i))
+        } else {
+            self.index.len() == self.entries.len()
+        }
+    }
+
+    fn rebuild_index(&mut self) {
+        self.index.clear();
+
+        if self.entries.len() <= INDEX_THRESHOLD {
+            return;
+        }
+
+        for (i, (key, _)) in self.entries.iter().enumerate() {
+            self.index.entry(key.clone()).or_insert(i);
+        }
+    }
+}
+
+/// Objects are equal when they have the same members, regardless of order.
+/// Repeated keys must have the same values in the same order.
+impl PartialEq for Object<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        let mut pairs = Vec::new();
+
+        self.pair_members(other, &mut pairs) && pairs.into_iter().all(|(a, b)| a == b)
+    }
+}
+
+impl Drop for Object<'_> {
+    fn drop(&mut self) {
+        if self.values().any(Value::is_container) {
+            drop_iteratively(
+                mem::take(&mut self.entries)
+                    .into_iter()
+                    .map(|(_, value)| value)
+                    .collect(),
+            );
+        }
+    }
+}
+
+impl<'a, K: Into<Cow<'a, str>>> FromIterator<(K, Value<'a>)> for Object<'a> {
+    fn from_iter<I: IntoIterator<Item = (K, Value<'a>)>>(iter: I) -> Self {
+        let mut object = Self::new();
+
+        for (key, value) in iter {
+            object.insert(key, value);
+        }
+
+        object
+    }
+}
+
+impl<'a> IntoIterator for Object<'a> {
+    type Item = (Cow<'a, str>, Value<'a>);
+    type IntoIter = vec::IntoIter<(Cow<'a, str>, Value<'a>)>;
+
+    fn into_iter(mut self) -> Self::IntoIter {
+        self.index.clear();
+        mem::take(&mut self.entries).into_iter()
+    }
+}
+
+impl<'b, 'a> IntoIterator for &'b Object<'a> {
+    type Item = (&'b str, &'b Value<'a>);
+    type IntoIter = Iter<'b, 'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'b, 'a> IntoIterator for &'b mut Object<'a> {
+    type Item = (&'b str, &'b mut Value<'a>);
+    type IntoIter = IterMut<'b, 'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+pub struct Iter<'b, 'a>(slice::Iter<'b, (Cow<'a, str>, Value<'a>)>);
+
+impl<'b, 'a> Iterator for Iter<'b, 'a> {
+    type Item = (&'b str, &'b Value<'a>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|(key, value)| (key.as_ref(), value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for Iter<'_, '_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.next_back().map(|(key, value)| (key.as_ref(), value))
+    }
+}
+
+impl ExactSizeIterator for Iter<'_, '_> {}
+
+pub struct IterMut<'b, 'a>(slice::IterMut<'b, (Cow<'a, str>, Value<'a>)>);
+
+impl<'b, 'a> Iterator for IterMut<'b, 'a> {
+    type Item = (&'b str, &'b mut Value<'a>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(split_mut)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for IterMut<'_, '_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.next_back().map(split_mut)
+    }
+}
+
+impl ExactSizeIterator for IterMut<'_, '_> {}
+
+fn split_mut<'b, 'a>(entry: &'b mut (Cow<'a, str>, Value<'a>)) -> (&'b str, &'b mut Value<'a>) {
+    let (key, value) = entry;
+    let key: &'b Cow<'a, str> = key;
+
+    (key.as_ref(), value)
+}
@@ -1,39 +1,26 @@
 use super::*;
-use ParserError::*;
+use ParserErrorKind::*;
 
-fn expect_success(input: &str) -> Value {
-    let bytes = input.as_bytes();
-    let mut lexer = crate::lexer::Lexer::new(bytes);
-    let tokens = lexer.lex().expect("Lexing failed");
-
-    let mut parser = Parser::new(tokens, bytes);
-    let result = parser.parse();
-
-    match result {
-        Some(Ok(value)) => value,
-        Some(Err(e)) => panic!("Expected success, but failed: {e}"),
-        None => panic!("Missing result"),
-    }
+fn expect_failure(input: &str) -> ParserError {
+    expect_failure_with(input, ParserOptions::default())
 }
 
-fn expect_failure(input: &str) -> ParserError {
+/// The failing counterpart of [`parse_with`].
+fn expect_failure_with(input: &str, options: ParserOptions) -> ParserError {
     let bytes = input.as_bytes();
-    let mut lexer = crate::lexer::Lexer::new(bytes);
-    let tokens = lexer.lex().expect("Lexing failed");
+    let tokens = crate::lexer::Lexer::new(bytes)
+        .lex()
+        .expect("Lexing failed");
 
-    let mut parser = Parser::new(tokens, bytes);
-    let result = parser.parse();
-
-    match result {
-        Some(Ok(_)) => panic!("Expected failure, but succeeded"),
-        Some(Err(e)) => e,
-        None => panic!("Missing result"),
+    match Parser::with_options(tokens, bytes, options).parse() {
+        Ok(_) => panic!("Expected failure, but succeeded"),
+        Err(e) => e,
     }
 }
 
 #[test]
 fn empty_object() {
-    let value = expect_success("{}");
+    let value = parse("{}");
 
     let Value::Object(obj) = value else {
         panic!("Expected object, got {value:?}");
@@ -44,7 +31,7 @@ fn empty_object() {
 
 #[test]
 fn empty_array() {
-    let value = expect_success("[]");
+    let value = parse("[]");
 
     let Value::Array(arr) = value else {
         panic!("Expected array, got {value:?}");
@@ -55,7 +42,7 @@ fn empty_array() {
 
 #[test]
 fn string_value() {
-    let value = expect_success(r#"{"key": "value"}"#);
+    let value = parse(r#"{"key": "value"}"#);
 
     let Value::Object(obj) = value else {
         panic!("Expected object, got {value:?}");
@@ -70,7 +57,7 @@ fn string_value() {
 
 #[test]
 fn number_value() {
-    let value = expect_success(r#"{"num": 42}"#);
+    let value = parse(r#"{"num": 42}"#);
 
     let Value::Object(obj) = value else {
         panic!("Expected object, got {value:?}");
@@ -80,7 +67,7 @@ fn number_value() {
         panic!("Expected number value");
     };
 
-    assert_eq!(*n, "42");
+    assert_eq!(n.as_str(), "42");
 }
 
 #[test]
@@ -89,7 +76,7 @@ fn boolean_values() {
         .iter()
         .for_each(|(input, expected)| {
             let input = format!(r#"{{"flag": {input}}}"#);
-            let value = expect_success(&input);
+            let value = parse(&input);
 
             let Value::Object(obj) = value else {
                 panic!("Expected object, got {value:?}");
@@ -105,7 +92,7 @@ fn boolean_values() {
 
 #[test]
 fn null_value() {
-    let value = expect_success(r#"{"empty": null}"#);
+    let value = parse(r#"{"empty": null}"#);
     let Value::Object(obj) = value else {
         panic!("Expected object, got {value:?}");
     };
@@ -115,32 +102,742 @@ fn null_value() {
     };
 }
 
+#[test]
+fn empty_document() {
+    ["", "   ", "\n\t\r "].iter().for_each(|input| {
+        let error = expect_failure(input);
+        assert!(matches!(error.kind, EmptyDocument));
+    });
+}
+
+#[test]
+fn object_preserves_key_order() {
+    let value = parse(r#"{"b": 1, "a": 2, "c": {"z": 3, "y": 4}}"#);
+
+    let Value::Object(obj) = value else {
+        panic!("Expected object, got {value:?}");
+    };
+
+    assert_eq!(obj.keys().collect::<Vec<_>>(), ["b", "a", "c"]);
+
+    let Some(Value::Object(inner)) = obj.get("c") else {
+        panic!("Expected object value");
+    };
+
+    assert_eq!(inner.keys().collect::<Vec<_>>(), ["z", "y"]);
+}
+
+#[test]
+fn large_object_lookup() {
+    let members: Vec<_> = (0..100).rev().map(|i| format!(r#""k{i}": {i}"#)).collect();
+    let input = format!("{{{}}}", members.join(", "));
+    let value = parse(&input);
+
+    let Value::Object(obj) = value else {
+        panic!("Expected object, got {value:?}");
+    };
+
+    assert_eq!(obj.len(), 100);
+    assert_eq!(obj.keys().next(), Some("k99"));
+    assert!((0..100).all(|i| obj.get(&format!("k{i}")) == Some(&Value::Number(i.into()))));
+
+    let input = format!("{{{}, \"k42\": 0}}", members.join(", "));
+    let error = expect_failure(&input);
+    assert!(matches!(error.kind, DuplicateKey { .. }));
+}
+
 #[test]
 fn invalid_start() {
     let error = expect_failure("]");
-    assert!(matches!(error, InvalidValue { .. }));
+    assert!(matches!(error.kind, InvalidValue { .. }));
 }
 
 #[test]
 fn duplicate_key() {
-    let error = expect_failure(r#"{"key": 1, "key": 2}"#);
-    assert!(matches!(error, DuplicateKey));
+    let error = expect_failure("{\"key\": 1,\n  \"key\": 2}");
+
+    assert!(matches!(
+        error.kind,
+        DuplicateKey { ref key, first_line: 1, first_col: 2 } if key == "key"
+    ));
+    assert_eq!((error.line, error.col), (2, 3));
 }
 
 #[test]
 fn missing_colon() {
     let error = expect_failure(r#"{"key" "value"}"#);
-    assert!(matches!(error, MissingColon));
+    assert!(matches!(error.kind, MissingColon));
 }
 
 #[test]
 fn invalid_key() {
     let error = expect_failure(r#"{123: "value"}"#);
-    assert!(matches!(error, InvalidKey));
+    assert!(matches!(error.kind, InvalidKey));
 }
 
 #[test]
 fn trailing_comma() {
     let error = expect_failure(r#"{"key": "value",}"#);
-    assert!(matches!(error, TrailingComma));
+    assert!(matches!(error.kind, TrailingComma));
+}
+
+#[test]
+fn array_trailing_comma() {
+    ["[1, 2,]", "[[],]", r#"{"key": ["value",]}"#]
+        .iter()
+        .for_each(|input| {
+            let error = expect_failure(input);
+            assert!(
+                matches!(error.kind, TrailingComma),
+                "unexpected error for {input}: {error:?}"
+            );
+        });
+}
+
+#[test]
+fn string_without_escapes_is_borrowed() {
+    let value = parse(r#""plain""#);
+
+    let Value::String(s) = value else {
+        panic!("Expected string, got {value:?}");
+    };
+
+    assert!(matches!(s, Cow::Borrowed("plain")));
+}
+
+#[test]
+fn string_escapes_are_decoded() {
+    let value = parse(r#""a\nb\t\"c\" \\ \/ \b\f\r""#);
+
+    let Value::String(s) = value else {
+        panic!("Expected string, got {value:?}");
+    };
+
+    assert!(matches!(s, Cow::Owned(_)));
+    assert_eq!(s, "a\nb\t\"c\" \\ / \u{08}\u{0C}\r");
+}
+
+#[test]
+fn unicode_escapes_are_decoded() {
+    let value = parse(r#""caf\u00e9 \u00C9""#);
+
+    let Value::String(s) = value else {
+        panic!("Expected string, got {value:?}");
+    };
+
+    assert_eq!(s, "café É");
+}
+
+#[test]
+fn surrogate_pairs_are_combined() {
+    let value = parse(r#""\uD83D\uDE00!""#);
+
+    let Value::String(s) = value else {
+        panic!("Expected string, got {value:?}");
+    };
+
+    assert_eq!(s, "\u{1F600}!");
+}
+
+#[test]
+fn object_keys_are_decoded() {
+    let value = parse(r#"{"\u0061bc": 1}"#);
+
+    let Value::Object(obj) = value else {
+        panic!("Expected object, got {value:?}");
+    };
+
+    assert!(obj.contains_key("abc"));
+}
+
+#[test]
+fn escaped_keys_are_compared_after_decoding() {
+    let error = expect_failure(r#"{"a": 1, "\u0061": 2}"#);
+    assert!(matches!(error.kind, DuplicateKey { .. }));
+}
+
+#[test]
+fn trailing_tokens() {
+    [
+        ("{} {}", Token::Lcurl, 3),
+        ("[1] 2", Token::Number(4..5), 4),
+    ]
+    .into_iter()
+    .for_each(|(input, found, offset)| {
+        let error = expect_failure(input);
+
+        assert!(
+            matches!(error.kind, TrailingTokens { found: ref f } if *f == found),
+            "unexpected error for {input}: {error:?}"
+        );
+        assert_eq!(error.offset, offset);
+    });
+
+    let error = expect_failure(r#""a" ]"#);
+    assert!(matches!(
+        error.kind,
+        TrailingTokens {
+            found: Token::Rsquare
+        }
+    ));
+    assert_eq!(error.offset, 4);
+}
+
+#[test]
+fn trailing_tokens_allowed_when_parsing_prefix() {
+    let input = "[1] 2";
+    let options = ParserOptions {
+        allow_trailing_tokens: true,
+        ..Default::default()
+    };
+
+    let value = parse_with(input, options);
+
+    assert!(matches!(value, Value::Array(arr) if arr.len() == 1));
+}
+
+#[test]
+fn error_positions() {
+    [
+        ("{\n  \"key\" \"value\"\n}", 2, 9, 10),
+        ("{\"a\": 1,\n \"a\": 2}", 2, 2, 10),
+        ("[1, 2,]", 1, 6, 5),
+        ("[1 2]", 1, 4, 3),
+        ("{1: 2}", 1, 2, 1),
+        ("[1,", 1, 4, 3),
+        ("", 1, 1, 0),
+    ]
+    .iter()
+    .for_each(|(input, line, col, offset)| {
+        let error = expect_failure(input);
+
+        assert_eq!(
+            (error.line, error.col, error.offset),
+            (*line, *col, *offset),
+            "unexpected position for {input:?}: {error}"
+        );
+    });
+}
+
+#[test]
+fn error_positions_from_lexer_spans() {
+    let input = "{\"a\": 1,\n \"a\": [1 2]}";
+    let bytes = input.as_bytes();
+    let (tokens, spans) = crate::lexer::Lexer::new(bytes)
+        .lex_with_spans()
+        .expect("Lexing failed");
+
+    let error = Parser::with_spans(tokens, spans, bytes, ParserOptions::default())
+        .parse()
+        .expect_err("Expected failure");
+
+    assert_eq!(error.to_string(), expect_failure(input).to_string());
+    assert_eq!((error.line, error.col, error.offset), (2, 2, 10));
+}
+
+#[test]
+fn error_display_includes_position() {
+    let error = expect_failure("{\"key\": 1,\n\"key\": 2}");
+
+    assert_eq!(
+        error.to_string(),
+        "Error at line 2, col 1: Keys must be unique within an object, \"key\" was first defined at line 1, col 2"
+    );
+}
+
+#[test]
+fn depth_limit_exceeded() {
+    let input = format!("{}{}", "[".repeat(200_000), "]".repeat(200_000));
+    let error = expect_failure(&input);
+
+    assert!(matches!(
+        error.kind,
+        DepthLimitExceeded {
+            depth: DEFAULT_MAX_DEPTH
+        }
+    ));
+    assert_eq!(error.offset, DEFAULT_MAX_DEPTH);
+}
+
+#[test]
+fn depth_limit_is_configurable() {
+    let options = ParserOptions {
+        max_depth: Some(2),
+        ..Default::default()
+    };
+
+    parse_with(r#"{"a": [1]}"#, options.clone());
+
+    let error = expect_failure_with(r#"{"a": [{}]}"#, options);
+    assert!(matches!(error.kind, DepthLimitExceeded { depth: 2 }));
+    assert_eq!((error.line, error.col), (1, 8));
+}
+
+#[test]
+fn iterative_matches_recursive() {
+    let iterative = ParserOptions {
+        iterative: true,
+        ..Default::default()
+    };
+
+    [
+        "{}",
+        "[]",
+        "[[], {}]",
+        r#"{"a": [1, {"b": null}, "c"], "d": {"e": [true, false]}}"#,
+        r#"[{"k": "v\n"}, [[[]]], -1.5e3]"#,
+        r#""scalar""#,
+    ]
+    .iter()
+    .for_each(|input| {
+        let recursive = parse(input);
+        let iterative = parse_with(input, iterative.clone());
+
+        assert_eq!(recursive, iterative, "mismatch for {input}");
+    });
+
+    [
+        "[1, 2,]",
+        r#"{"a": 1,}"#,
+        r#"{"a" 1}"#,
+        r#"{"a": 1, "a": 2}"#,
+        "{1: 2}",
+        "[1 2]",
+        r#"{"a": 1 "b": 2}"#,
+        "[1,",
+        r#"{"a":"#,
+        "[] 1",
+        "]",
+    ]
+    .iter()
+    .for_each(|input| {
+        let recursive = expect_failure(input);
+        let iterative = expect_failure_with(input, iterative.clone());
+
+        assert_eq!(
+            (recursive.kind.to_string(), recursive.offset),
+            (iterative.kind.to_string(), iterative.offset),
+            "mismatch for {input}"
+        );
+    });
+}
+
+#[test]
+fn iterative_respects_depth_limit() {
+    let options = ParserOptions {
+        iterative: true,
+        max_depth: Some(2),
+        ..Default::default()
+    };
+
+    parse_with("[[]]", options.clone());
+
+    let error = expect_failure_with("[[{}]]", options);
+    assert!(matches!(error.kind, DepthLimitExceeded { depth: 2 }));
+    assert_eq!(error.offset, 2);
+}
+
+#[test]
+fn iterative_parses_and_drops_very_deep_documents() {
+    let value = parse_deep(r#"{"a": ["#, "");
+    assert!(matches!(value, Value::Object(ref obj) if obj.contains_key("a")));
+}
+
+#[test]
+fn object_insert_and_remove_keep_order() {
+    let mut obj: Object = (0..20).map(|i| (i.to_string(), Value::Null)).collect();
+
+    assert_eq!(obj.insert("5", Value::Boolean(true)), Some(Value::Null));
+    assert_eq!(obj.remove("0"), Some(Value::Null));
+    assert_eq!(obj.remove("0"), None);
+    assert_eq!(obj.insert("0", Value::Null), None);
+
+    let keys: Vec<_> = obj.keys().collect();
+    assert_eq!(keys.first(), Some(&"1"));
+    assert_eq!(keys.last(), Some(&"0"));
+    assert_eq!(obj.get_index(4), Some(("5", &Value::Boolean(true))));
+    assert_eq!(obj.get("19"), Some(&Value::Null));
+}
+
+#[test]
+fn duplicate_key_points_at_first_occurrence_in_same_object() {
+    let error = expect_failure(r#"{"a": {"b": 1}, "b": [{"b": 2}], "c": 3, "b": 4}"#);
+
+    assert!(matches!(
+        error.kind,
+        DuplicateKey { ref key, first_line: 1, first_col: 17 } if key == "b"
+    ));
+    assert_eq!(error.col, 42);
+}
+
+#[test]
+fn duplicate_key_policies() {
+    let input = r#"{"a": 1, "b": 2, "a": 3}"#;
+
+    [
+        (DuplicateKeys::FirstWins, vec![("a", "1"), ("b", "2")]),
+        (DuplicateKeys::LastWins, vec![("a", "3"), ("b", "2")]),
+        (
+            DuplicateKeys::KeepAll,
+            vec![("a", "1"), ("b", "2"), ("a", "3")],
+        ),
+    ]
+    .into_iter()
+    .for_each(|(duplicate_keys, expected)| {
+        [false, true].into_iter().for_each(|iterative| {
+            let options = ParserOptions {
+                duplicate_keys,
+                iterative,
+                ..Default::default()
+            };
+
+            let Value::Object(obj) = parse_with(input, options) else {
+                panic!("Expected object for {duplicate_keys:?}");
+            };
+
+            let members: Vec<_> = obj
+                .iter()
+                .map(|(key, value)| match value {
+                    Value::Number(n) => (key, n.as_str()),
+                    other => panic!("Expected number, got {other:?}"),
+                })
+                .collect();
+
+            assert_eq!(members, expected, "mismatch for {duplicate_keys:?}");
+        });
+    });
+}
+
+#[test]
+fn keep_all_exposes_every_duplicate() {
+    let options = ParserOptions {
+        duplicate_keys: DuplicateKeys::KeepAll,
+        ..Default::default()
+    };
+
+    let Value::Object(obj) = parse_with(r#"{"a": 1, "a": 2}"#, options) else {
+        panic!("Expected object");
+    };
+
+    assert_eq!(obj.get("a"), Some(&Value::Number(1.into())));
+    assert_eq!(
+        obj.get_all("a").collect::<Vec<_>>(),
+        [&Value::Number(1.into()), &Value::Number(2.into())]
+    );
+}
+
+fn number(input: &str) -> Number<'_> {
+    Number::parse(input).expect("expected a valid number")
+}
+
+#[test]
+fn number_integer_accessors() {
+    assert_eq!(number("42").as_i64(), Ok(42));
+    assert_eq!(number("-0").as_i64(), Ok(0));
+    assert_eq!(number("1.0").as_i64(), Ok(1));
+    assert_eq!(number("25e2").as_u64(), Ok(2500));
+    assert_eq!(number("1500e-2").as_i64(), Ok(15));
+    assert_eq!(number("18446744073709551615").as_u64(), Ok(u64::MAX));
+    assert_eq!(
+        number("-170141183460469231731687303715884105728").as_i128(),
+        Ok(i128::MIN)
+    );
+
+    assert_eq!(
+        number("1.5").as_i64(),
+        Err(NumberConversionError::Fractional)
+    );
+    assert_eq!(
+        number("-1").as_u64(),
+        Err(NumberConversionError::OutOfRange)
+    );
+    assert_eq!(
+        number("9223372036854775808").as_i64(),
+        Err(NumberConversionError::OutOfRange)
+    );
+    assert_eq!(
+        number("1e40").as_i128(),
+        Err(NumberConversionError::OutOfRange)
+    );
+    assert_eq!(
+        number("1e999999999999999999999").as_i64(),
+        Err(NumberConversionError::OutOfRange)
+    );
+}
+
+#[test]
+fn number_float_accessors() {
+    assert_eq!(number("1.5").as_f64(), Ok(1.5));
+    assert_eq!(number("0.1").as_f64(), Ok(0.1));
+    assert_eq!(number("-2.5e-3").as_f64(), Ok(-0.0025));
+    assert_eq!(
+        number("12345678901234567890").as_f64(),
+        Err(NumberConversionError::PrecisionLoss)
+    );
+    assert_eq!(
+        number("1e400").as_f64(),
+        Err(NumberConversionError::OutOfRange)
+    );
+    assert_eq!(
+        number("12345678901234567890").as_f64_lossy(),
+        1.2345678901234567e19
+    );
+}
+
+#[test]
+fn number_decimal_is_exact() {
+    [
+        (
+            "12345678901234567890.123456789",
+            "12345678901234567890.123456789",
+        ),
+        ("1.50", "1.5"),
+        ("-0.000", "0"),
+        ("1e2", "100"),
+        ("1e30", "1e30"),
+        ("0.00012", "0.00012"),
+        ("-12.5e-10", "-1.25e-9"),
+    ]
+    .iter()
+    .for_each(|(input, expected)| {
+        let decimal = number(input).as_decimal();
+
+        assert_eq!(decimal.to_string(), *expected);
+        assert_eq!(decimal.to_string().parse::<Decimal>(), Ok(decimal));
+    });
+}
+
+#[test]
+fn number_comparisons_are_numeric() {
+    assert_eq!(number("1"), number("1.0"));
+    assert_eq!(number("100"), number("1e2"));
+    assert_ne!(number("1"), number("1.0000000000000000000001"));
+    assert!(number("-2") < number("-1.5"));
+    assert!(number("0.999") < number("1"));
+    assert!(number("-0") == number("0"));
+}
+
+#[test]
+fn number_parse_rejects_invalid_text() {
+    ["", "01", "1.", "+1", "1 2", "NaN", "-"]
+        .iter()
+        .for_each(|input| assert!(Number::parse(input).is_none(), "accepted {input:?}"));
+
+    assert_eq!(Number::from_f64(f64::NAN), None);
+    assert_eq!(
+        Number::from_f64(1e300).map(|n| n.to_string()).as_deref(),
+        Some("1e300")
+    );
+    assert_eq!(
+        Number::from_f64(1.0).map(|n| n.to_string()).as_deref(),
+        Some("1.0")
+    );
+}
+
+fn minify(input: &str) -> std::result::Result<String, MinifyError> {
+    let bytes = input.as_bytes();
+    let tokens = crate::lexer::Lexer::new(bytes)
+        .lex()
+        .expect("Lexing failed");
+
+    let mut output = Vec::new();
+    Parser::new(tokens, bytes).minify(&mut output)?;
+
+    Ok(String::from_utf8(output).expect("Minified output is not UTF-8"))
+}
+
+#[test]
+fn minify_matches_compact_serializer() {
+    let inputs = [
+        "{}",
+        "[ ]",
+        " 42 ",
+        "\"text\"",
+        "{ \"a\" : [ 1, 2.5e3, -0 ], \"b\" : { \"c\" : null, \"d\" : [ true, false ] } }",
+        "[ [ [ ] ], { }, [ { \"x\" : { } } ] ]",
+        "[\n\t{ \"z\": 1,\n  \"a\": 2 }\n]",
+    ];
+
+    for input in inputs {
+        let expected = crate::serializer::to_string(&parse(input));
+        assert_eq!(minify(input).unwrap(), expected, "input: {input}");
+    }
+}
+
+#[test]
+fn minify_copies_strings_and_numbers_verbatim() {
+    let input = r#"{ "a\/b" : "A\n" , "n" : 1.50E+2 }"#;
+
+    assert_eq!(minify(input).unwrap(), r#"{"a\/b":"A\n","n":1.50E+2}"#);
+}
+
+#[test]
+fn minify_reports_the_same_errors_as_parse() {
+    let inputs = [
+        "",
+        "[1, 2,]",
+        "[1 2]",
+        "{\"a\" 1}",
+        "{1: 2}",
+        "{\"a\": 1,}",
+        "{\"a\": }",
+        "[1, 2",
+        "{} []",
+        "{\"a\": 1, \"b\": {\"a\": 2}, \"a\": 3}",
+        "{\"a\\u0062\": 1, \"ab\": 2}",
+    ];
+
+    for input in inputs {
+        let expected = expect_failure(input);
+        let Err(MinifyError::Parser(error)) = minify(input) else {
+            panic!("Expected minify to fail for {input:?}");
+        };
+
+        assert_eq!(error.to_string(), expected.to_string(), "input: {input}");
+    }
+}
+
+#[test]
+fn minify_applies_duplicate_key_policy() {
+    let bytes = br#"{"a": 1, "a": 2}"#;
+    let minify_with = |duplicate_keys| {
+        let tokens = crate::lexer::Lexer::new(bytes).lex().unwrap();
+        let options = ParserOptions {
+            duplicate_keys,
+            ..Default::default()
+        };
+        let mut output = Vec::new();
+
+        Parser::with_options(tokens, bytes, options)
+            .minify(&mut output)
+            .map(|_| String::from_utf8(output).unwrap())
+    };
+
+    assert_eq!(
+        minify_with(DuplicateKeys::KeepAll).unwrap(),
+        r#"{"a":1,"a":2}"#
+    );
+    assert!(matches!(
+        minify_with(DuplicateKeys::Error),
+        Err(MinifyError::Parser(ParserError {
+            kind: DuplicateKey { .. },
+            ..
+        }))
+    ));
+
+    for policy in [DuplicateKeys::FirstWins, DuplicateKeys::LastWins] {
+        assert!(matches!(
+            minify_with(policy),
+            Err(MinifyError::UnsupportedDuplicateKeys(p)) if p == policy
+        ));
+    }
+}
+
+#[test]
+fn minify_respects_max_depth() {
+    let bytes = b"[[[1]]]";
+    let tokens = crate::lexer::Lexer::new(bytes).lex().unwrap();
+    let options = ParserOptions {
+        max_depth: Some(2),
+        ..Default::default()
+    };
+
+    let result = Parser::with_options(tokens, bytes, options).minify(&mut Vec::new());
+
+    assert!(matches!(
+        result,
+        Err(MinifyError::Parser(ParserError {
+            kind: DepthLimitExceeded { depth: 2 },
+            ..
+        }))
+    ));
+}
+
+#[test]
+fn into_owned_outlives_the_input() {
+    let input = String::from(
+        r#"{"name": "ada", "escaped": "a\nb", "list": [1.5, true, null, {}], "name": 2}"#,
+    );
+    let options = ParserOptions {
+        duplicate_keys: DuplicateKeys::KeepAll,
+        ..Default::default()
+    };
+    let borrowed = parse_with(&input, options);
+    let expected = borrowed.clone();
+
+    let owned: OwnedValue = borrowed.into_owned();
+    assert_eq!(owned, expected);
+    drop(expected);
+    drop(input);
+
+    let owned = std::thread::spawn(move || owned).join().unwrap();
+    let Value::Object(object) = &owned else {
+        panic!("Expected object, got {owned:?}");
+    };
+
+    assert_eq!(
+        object.keys().collect::<Vec<_>>(),
+        ["name", "escaped", "list", "name"]
+    );
+    assert!(matches!(object.get("name"), Some(Value::String(Cow::Owned(s))) if s == "ada"));
+}
+
+#[test]
+fn into_owned_handles_very_deep_documents() {
+    let value = parse_deep(r#"{"a": ["#, "").into_owned();
+    assert!(matches!(value, Value::Object(ref obj) if obj.contains_key("a")));
+}
+
+#[test]
+fn clone_and_eq_handle_very_deep_documents() {
+    let value = parse_deep(r#"{"a": ["#, "");
+    let other = parse_deep(r#"{"a": ["#, "1");
+
+    assert_eq!(value.clone(), value);
+    assert_ne!(value, other);
+}
+
+#[test]
+fn value_accessors() {
+    let value = parse(r#"{"name": "ada", "admin": true, "tags": ["a", null], "age": 36}"#);
+
+    assert_eq!(value.kind(), ValueKind::Object);
+    assert_eq!(value.get("name").and_then(Value::as_str), Some("ada"));
+    assert_eq!(value["admin"].as_bool(), Some(true));
+    assert_eq!(value["age"].as_number().map(|n| n.as_u64()), Some(Ok(36)));
+    assert_eq!(value["tags"].as_array().map(|tags| tags.len()), Some(2));
+    assert_eq!(
+        value["tags"].get_index(0).and_then(Value::as_str),
+        Some("a")
+    );
+    assert!(value["tags"][1].is_null());
+    assert_eq!(value.as_object().map(Object::len), Some(4));
+
+    assert!(value.get_index(0).is_none());
+    assert!(value["tags"].get("a").is_none());
+    assert_eq!(value["name"].as_bool(), None);
+    assert_eq!(value["age"].kind().to_string(), "number");
+}
+
+#[test]
+fn value_index_gives_null_on_miss() {
+    let value = parse(r#"{"a": [{"b": 1}]}"#);
+
+    assert!(value["missing"].is_null());
+    assert!(value["a"][5].is_null());
+    assert!(value["a"][0]["b"]["c"][0].is_null());
+    assert!(value[0].is_null());
+}
+
+#[test]
+fn value_mutable_accessors() {
+    let mut value = parse(r#"{"a": [1, 2]}"#);
+
+    *value.get_mut("a").and_then(|a| a.get_index_mut(1)).unwrap() = Value::Null;
+    value
+        .as_object_mut()
+        .unwrap()
+        .insert("b", Value::Boolean(false));
+
+    assert_eq!(value, parse(r#"{"a": [1, null], "b": false}"#));
 }
@@ -0,0 +1,63 @@
+/// Decodes the escape sequences of a JSON string body.
+///
+/// The lexer has already validated every escape, so anything unexpected is
+/// copied through verbatim rather than reported.
+pub(crate) fn unescape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('"') => out.push('"'),
+            Some('\\') => out.push('\\'),
+            Some('/') => out.push('/'),
+            Some('b') => out.push('\u{08}'),
+            Some('f') => out.push('\u{0C}'),
+            Some('n') => out.push('\n'),
+            Some('r') => out.push('\r'),
+            Some('t') => out.push('\t'),
+            Some('u') => {
+                let rest = chars.as_str();
+
+                let Some(high) = read_hex(rest) else {
+                    out.push_str("\\u");
+                    continue;
+                };
+
+                let rest = &rest[4..];
+                let low = rest
+                    .strip_prefix("\\u")
+                    .and_then(read_hex)
+                    .filter(|low| (0xDC00..=0xDFFF).contains(low));
+
+                match low {
+                    Some(low) if (0xD800..=0xDBFF).contains(&high) => {
+                        let code = 0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00);
+                        out.push(char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER));
+                        chars = rest[6..].chars();
+                    }
+                    _ => {
+                        out.push(char::from_u32(high).unwrap_or(char::REPLACEMENT_CHARACTER));
+                        chars = rest.chars();
+                    }
+                }
+            }
+            Some(other) => {
+                out.push('\\');
+                out.push(other);
+            }
+            None => out.push('\\'),
+        }
+    }
+
+    out
+}
+
+fn read_hex(s: &str) -> Option<u32> {
+    s.get(..4).and_then(|hex| u32::from_str_radix(hex, 16).ok())
+}
@@ -0,0 +1,421 @@
+use std::{
+    borrow::Cow,
+    fmt, mem,
+    ops::{Deref, DerefMut, Index},
+    slice, vec,
+};
+
+use super::{Number, Object};
+
+#[derive(Debug)]
+pub enum Value<'a> {
+    Object(Object<'a>),
+    Array(Array<'a>),
+    String(Cow<'a, str>),
+    Number(Number<'a>),
+    Boolean(bool),
+    Null,
+}
+
+/// Returned by indexing when the key or index is missing.
+static NULL: Value<'static> = Value::Null;
+
+/// The type of a json value, without its contents.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ValueKind {
+    Object,
+    Array,
+    String,
+    Number,
+    Boolean,
+    Null,
+}
+
+impl fmt::Display for ValueKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            ValueKind::Object => "object",
+            ValueKind::Array => "array",
+            ValueKind::String => "string",
+            ValueKind::Number => "number",
+            ValueKind::Boolean => "boolean",
+            ValueKind::Null => "null",
+        };
+
+        f.write_str(s)
+    }
+}
+
+/// A value that owns all of its strings and numbers, so that it can outlive
+/// the input it was parsed from, be sent to other threads or be cached.
+///
+/// Since `Value` is covariant, an `OwnedValue` can be used anywhere a
+/// borrowed `Value<'a>` is expected.
+pub type OwnedValue = Value<'static>;
+
+impl<'a> Value<'a> {
+    pub fn kind(&self) -> ValueKind {
+        match self {
+            Value::Object(_) => ValueKind::Object,
+            Value::Array(_) => ValueKind::Array,
+            Value::String(_) => ValueKind::String,
+            Value::Number(_) => ValueKind::Number,
+            Value::Boolean(_) => ValueKind::Boolean,
+            Value::Null => ValueKind::Null,
+        }
+    }
+
+    /// Returns the member named `key` if this is an object that has one.
+    pub fn get(&self, key: &str) -> Option<&Value<'a>> {
+        self.as_object()?.get(key)
+    }
+
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut Value<'a>> {
+        self.as_object_mut()?.get_mut(key)
+    }
+
+    /// Returns the element at `index` if this is an array that long.
+    pub fn get_index(&self, index: usize) -> Option<&Value<'a>> {
+        self.as_array()?.get(index)
+    }
+
+    pub fn get_index_mut(&mut self, index: usize) -> Option<&mut Value<'a>> {
+        self.as_array_mut()?.get_mut(index)
+    }
+
+    pub fn as_object(&self) -> Option<&Object<'a>> {
+        match self {
+            Value::Object(object) => Some(object),
+            _ => None,
+        }
+    }
+
+    pub fn as_object_mut(&mut self) -> Option<&mut Object<'a>> {
+        match self {
+            Value::Object(object) => Some(object),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&Array<'a>> {
+        match self {
+            Value::Array(array) => Some(array),
+            _ => None,
+        }
+    }
+
+    pub fn as_array_mut(&mut self) -> Option<&mut Array<'a>> {
+        match self {
+            Value::Array(array) => Some(array),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_number(&self) -> Option<&Number<'a>> {
+        match self {
+            Value::Number(number) => Some(number),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Value::Boolean(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    pub fn is_null(&self) -> bool {
+        matches!(self, Value::Null)
+    }
+
+    pub(super) fn is_container(&self) -> bool {
+        matches!(self, Value::Object(_) | Value::Array(_))
+    }
+
+    /// Copies every string and number still borrowed from the input,
+    /// detaching the value from it.
+    pub fn into_owned(self) -> OwnedValue {
+        enum Frame<'a> {
+            Array(Array<'static>, vec::IntoIter<Value<'a>>),
+            Object(
+                Object<'static>,
+                vec::IntoIter<(Cow<'a, str>, Value<'a>)>,
+                Cow<'static, str>,
+            ),
+        }
+
+        let mut stack = Vec::new();
+        let mut value = self;
+
+        loop {
+            let mut owned = match value {
+                Value::Array(array) => {
+                    stack.push(Frame::Array(Array::new(), array.into_iter()));
+                    None
+                }
+                Value::Object(object) => {
+                    stack.push(Frame::Object(
+                        Object::new(),
+                        object.into_iter(),
+                        Cow::Borrowed(""),
+                    ));
+                    None
+                }
+                Value::String(s) => Some(Value::String(Cow::Owned(s.into_owned()))),
+                Value::Number(number) => Some(Value::Number(number.into_owned())),
+                Value::Boolean(b) => Some(Value::Boolean(b)),
+                Value::Null => Some(Value::Null),
+            };
+
+            // Hand finished values to their parents until one of them has
+            // another child to convert.
+            loop {
+                let Some(frame) = stack.last_mut() else {
+                    return owned.expect("an empty stack means the root is finished");
+                };
+
+                if let Some(owned) = owned.take() {
+                    match frame {
+                        Frame::Array(array, _) => array.push(owned),
+                        Frame::Object(object, _, key) => object.push(mem::take(key), owned),
+                    }
+                }
+
+                let next = match frame {
+                    Frame::Array(_, children) => children.next(),
+                    Frame::Object(_, members, key) => members.next().map(|(k, child)| {
+                        *key = Cow::Owned(k.into_owned());
+                        child
+                    }),
+                };
+
+                if let Some(child) = next {
+                    value = child;
+                    break;
+                }
+
+                owned = match stack.pop() {
+                    Some(Frame::Array(array, _)) => Some(Value::Array(array)),
+                    Some(Frame::Object(object, _, _)) => Some(Value::Object(object)),
+                    None => unreachable!("the frame was just inspected"),
+                };
+            }
+        }
+    }
+}
+
+impl<'a> Clone for Value<'a> {
+    fn clone(&self) -> Self {
+        enum Frame<'b, 'a> {
+            Array(Array<'a>, slice::Iter<'b, Value<'a>>),
+            Object(
+                Object<'a>,
+                slice::Iter<'b, (Cow<'a, str>, Value<'a>)>,
+                Cow<'a, str>,
+            ),
+        }
+
+        let mut stack = Vec::new();
+        let mut value = self;
+
+        loop {
+            let mut cloned = match value {
+                Value::Array(array) => {
+                    stack.push(Frame::Array(Array::new(), array.iter()));
+                    None
+                }
+                Value::Object(object) => {
+                    stack.push(Frame::Object(
+                        Object::new(),
+                        object.entries(),
+                        Cow::Borrowed(""),
+                    ));
+                    None
+                }
+                Value::String(s) => Some(Value::String(s.clone())),
+                Value::Number(number) => Some(Value::Number(number.clone())),
+                Value::Boolean(b) => Some(Value::Boolean(*b)),
+                Value::Null => Some(Value::Null),
+            };
+
+            loop {
+                let Some(frame) = stack.last_mut() else {
+                    return cloned.expect("an empty stack means the root is finished");
+                };
+
+                if let Some(cloned) = cloned.take() {
+                    match frame {
+                        Frame::Array(array, _) => array.push(cloned),
+                        Frame::Object(object, _, key) => object.push(mem::take(key), cloned),
+                    }
+                }
+
+                let next = match frame {
+                    Frame::Array(_, children) => children.next(),
+                    Frame::Object(_, members, key) => members.next().map(|(k, child)| {
+                        *key = k.clone();
+                        child
+                    }),
+                };
+
+                if let Some(child) = next {
+                    value = child;
+                    break;
+                }
+
+                cloned = match stack.pop() {
+                    Some(Frame::Array(array, _)) => Some(Value::Array(array)),
+                    Some(Frame::Object(object, _, _)) => Some(Value::Object(object)),
+                    None => unreachable!("the frame was just inspected"),
+                };
+            }
+        }
+    }
+}
+
+impl PartialEq for Value<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        let mut pairs = vec![(self, other)];
+
+        while let Some(pair) = pairs.pop() {
+            let equal = match pair {
+                (Value::Array(a), Value::Array(b)) => {
+                    pairs.extend(a.iter().zip(b.iter()));
+                    a.len() == b.len()
+                }
+                (Value::Object(a), Value::Object(b)) => a.pair_members(b, &mut pairs),
+                (Value::String(a), Value::String(b)) => a == b,
+                (Value::Number(a), Value::Number(b)) => a == b,
+                (Value::Boolean(a), Value::Boolean(b)) => a == b,
+                (Value::Null, Value::Null) => true,
+                _ => false,
+            };
+
+            if !equal {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+/// Looks up an object member, giving `Null` if it is missing or if the value
+/// is not an object, so that lookups can be chained.
+impl<'a> Index<&str> for Value<'a> {
+    type Output = Value<'a>;
+
+    fn index(&self, key: &str) -> &Value<'a> {
+        self.get(key).unwrap_or(&NULL)
+    }
+}
+
+/// Looks up an array element, giving `Null` if it is out of range or if the
+/// value is not an array.
+impl<'a> Index<usize> for Value<'a> {
+    type Output = Value<'a>;
+
+    fn index(&self, index: usize) -> &Value<'a> {
+        self.get_index(index).unwrap_or(&NULL)
+    }
+}
+
+/// The elements of a json array.
+///
+/// Behaves like a `Vec` through `Deref`, but is dropped without recursion so
+/// that releasing a deeply nested document cannot overflow the stack. For the
+/// same reason, cloning and comparing values and [`Value::into_owned`] do not
+/// recurse either.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Array<'a>(Vec<Value<'a>>);
+
+impl<'a> Array<'a> {
+    pub fn new() -> Self {
+        Self(Vec::new())
+    }
+
+    pub fn into_vec(mut self) -> Vec<Value<'a>> {
+        mem::take(&mut self.0)
+    }
+
+    /// See [`Value::into_owned`].
+    pub fn into_owned(self) -> Array<'static> {
+        match Value::Array(self).into_owned() {
+            Value::Array(array) => array,
+            _ => unreachable!("arrays stay arrays"),
+        }
+    }
+}
+
+impl<'a> Deref for Array<'a> {
+    type Target = Vec<Value<'a>>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for Array<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<'a> From<Vec<Value<'a>>> for Array<'a> {
+    fn from(values: Vec<Value<'a>>) -> Self {
+        Self(values)
+    }
+}
+
+impl<'a> FromIterator<Value<'a>> for Array<'a> {
+    fn from_iter<I: IntoIterator<Item = Value<'a>>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl<'a> IntoIterator for Array<'a> {
+    type Item = Value<'a>;
+    type IntoIter = std::vec::IntoIter<Value<'a>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.into_vec().into_iter()
+    }
+}
+
+impl<'a, 'b> IntoIterator for &'b Array<'a> {
+    type Item = &'b Value<'a>;
+    type IntoIter = std::slice::Iter<'b, Value<'a>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+impl Drop for Array<'_> {
+    fn drop(&mut self) {
+        if self.0.iter().any(Value::is_container) {
+            drop_iteratively(mem::take(&mut self.0));
+        }
+    }
+}
+
+/// Drops nested values using a heap allocated stack instead of recursion.
+/// Every container is emptied before it goes out of scope, so its own `Drop`
+/// has nothing left to recurse into.
+pub(super) fn drop_iteratively(mut stack: Vec<Value<'_>>) {
+    while let Some(value) = stack.pop() {
+        match value {
+            Value::Array(mut array) => stack.append(&mut array.0),
+            Value::Object(object) => stack.extend(object.into_iter().map(|(_, value)| value)),
+            _ => {}
+        }
+    }
+}
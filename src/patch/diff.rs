@@ -0,0 +1,231 @@
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+use super::PatchOperation;
+use crate::parser::{Array, Value};
+use crate::pointer::JsonPointer;
+
+/// Pending work for [`diff`]: a pair of values still to be compared, or an
+/// operation that is ready.
+enum Step<'b, 'a> {
+    Diff(&'b Value<'a>, &'b Value<'a>, Option<usize>),
+    Emit(PatchOperation),
+}
+
+/// Appends the operations that transform `from` into `to`.
+///
+/// Objects are compared member by member, so only the members that differ
+/// are touched. Arrays keep their common prefix and suffix, diff the
+/// elements in between pairwise and then add or remove the surplus, which
+/// turns a single insertion or removal anywhere into one operation. Moves
+/// and copies are never generated.
+pub(super) fn diff<'a>(from: &Value<'a>, to: &Value<'a>, operations: &mut Vec<PatchOperation>) {
+    let mut diff = Diff {
+        hashes: HashMap::new(),
+        paths: Paths::default(),
+    };
+    diff.hash(from);
+    diff.hash(to);
+
+    let mut stack = vec![Step::Diff(from, to, None)];
+
+    while let Some(step) = stack.pop() {
+        let (from, to, path) = match step {
+            Step::Diff(from, to, path) => (from, to, path),
+            Step::Emit(operation) => {
+                operations.push(operation);
+                continue;
+            }
+        };
+
+        if diff.same(from, to) {
+            continue;
+        }
+
+        // The steps for this pair are pushed in order and then reversed, so
+        // that they are popped in order.
+        let start = stack.len();
+
+        match (from, to) {
+            (Value::Object(from), Value::Object(to)) => {
+                for key in from.keys().filter(|key| !to.contains_key(key)) {
+                    stack.push(Step::Emit(PatchOperation::Remove {
+                        path: diff.paths.pointer(path, Token::Key(key)),
+                    }));
+                }
+
+                for (key, value) in to {
+                    stack.push(match from.get(key) {
+                        Some(old) => Step::Diff(old, value, diff.paths.push(path, Token::Key(key))),
+                        None => Step::Emit(PatchOperation::Add {
+                            path: diff.paths.pointer(path, Token::Key(key)),
+                            value: value.clone().into_owned(),
+                        }),
+                    });
+                }
+            }
+            (Value::Array(from), Value::Array(to)) => {
+                diff.arrays(from, to, path, &mut stack);
+            }
+            _ => stack.push(Step::Emit(PatchOperation::Replace {
+                path: diff.paths.resolve(path),
+                value: to.clone().into_owned(),
+            })),
+        }
+
+        stack[start..].reverse();
+    }
+}
+
+/// The state of one [`diff`].
+struct Diff<'b, 'a> {
+    /// A hash of every value in both documents, by address, so that telling
+    /// two values apart does not mean walking them again at every level.
+    hashes: HashMap<*const Value<'a>, u64>,
+    paths: Paths<'b>,
+}
+
+impl<'b, 'a> Diff<'b, 'a> {
+    /// Hashes `root` and everything in it, children before their parents.
+    /// Equal values get equal hashes, so objects combine their members in a
+    /// way that does not depend on order.
+    fn hash(&mut self, root: &Value<'a>) {
+        let mut stack = vec![(root, false)];
+
+        while let Some((value, children_done)) = stack.pop() {
+            if !children_done {
+                match value {
+                    Value::Array(array) => {
+                        stack.push((value, true));
+                        stack.extend(array.iter().map(|v| (v, false)));
+                        continue;
+                    }
+                    Value::Object(object) => {
+                        stack.push((value, true));
+                        stack.extend(object.values().map(|v| (v, false)));
+                        continue;
+                    }
+                    _ => {}
+                }
+            }
+
+            let mut hasher = DefaultHasher::new();
+            match value {
+                Value::Null => 0.hash(&mut hasher),
+                Value::Boolean(b) => (1, b).hash(&mut hasher),
+                Value::Number(number) => (2, number.as_decimal()).hash(&mut hasher),
+                Value::String(s) => (3, s).hash(&mut hasher),
+                Value::Array(array) => {
+                    (4, array.len()).hash(&mut hasher);
+                    array
+                        .iter()
+                        .for_each(|v| self.hashes[&(v as *const _)].hash(&mut hasher));
+                }
+                Value::Object(object) => {
+                    let members = object.iter().fold(0u64, |sum, (key, v)| {
+                        let mut member = DefaultHasher::new();
+                        (key, self.hashes[&(v as *const _)]).hash(&mut member);
+                        sum.wrapping_add(member.finish())
+                    });
+                    (5, object.len(), members).hash(&mut hasher);
+                }
+            }
+
+            self.hashes.insert(value, hasher.finish());
+        }
+    }
+
+    /// Whether `from` and `to` are equal, comparing them in full only when
+    /// their hashes already match.
+    fn same(&self, from: &Value<'a>, to: &Value<'a>) -> bool {
+        self.hashes[&(from as *const _)] == self.hashes[&(to as *const _)] && from == to
+    }
+
+    fn arrays(
+        &mut self,
+        from: &'b Array<'a>,
+        to: &'b Array<'a>,
+        path: Option<usize>,
+        stack: &mut Vec<Step<'b, 'a>>,
+    ) {
+        let prefix = from
+            .iter()
+            .zip(to.iter())
+            .take_while(|(a, b)| self.same(a, b))
+            .count();
+        let suffix = from[prefix..]
+            .iter()
+            .rev()
+            .zip(to[prefix..].iter().rev())
+            .take_while(|(a, b)| self.same(a, b))
+            .count();
+
+        let from = &from[prefix..from.len() - suffix];
+        let to = &to[prefix..to.len() - suffix];
+        let common = from.len().min(to.len());
+
+        for (i, (a, b)) in from.iter().zip(to.iter()).enumerate() {
+            let path = self.paths.push(path, Token::Index(prefix + i));
+            stack.push(Step::Diff(a, b, path));
+        }
+
+        // Remove from the back so that earlier indices stay valid.
+        for i in (common..from.len()).rev() {
+            stack.push(Step::Emit(PatchOperation::Remove {
+                path: self.paths.pointer(path, Token::Index(prefix + i)),
+            }));
+        }
+
+        for (i, value) in to.iter().enumerate().skip(common) {
+            stack.push(Step::Emit(PatchOperation::Add {
+                path: self.paths.pointer(path, Token::Index(prefix + i)),
+                value: value.clone().into_owned(),
+            }));
+        }
+    }
+}
+
+/// The paths of the pairs being compared, each stored as a token below its
+/// parent, like [`crate::path`] does, so that deep documents do not need a
+/// copy of every path. `None` stands for the root.
+#[derive(Default)]
+struct Paths<'b>(Vec<(Option<usize>, Token<'b>)>);
+
+#[derive(Clone, Copy)]
+enum Token<'b> {
+    Key(&'b str),
+    Index(usize),
+}
+
+impl<'b> Paths<'b> {
+    fn push(&mut self, parent: Option<usize>, token: Token<'b>) -> Option<usize> {
+        self.0.push((parent, token));
+        Some(self.0.len() - 1)
+    }
+
+    /// Builds the pointer to `path`.
+    fn resolve(&self, mut path: Option<usize>) -> JsonPointer {
+        let mut tokens = Vec::new();
+
+        while let Some(i) = path {
+            let (parent, token) = self.0[i];
+            tokens.push(token);
+            path = parent;
+        }
+
+        tokens
+            .into_iter()
+            .rev()
+            .map(|token| match token {
+                Token::Key(key) => key.to_string(),
+                Token::Index(i) => i.to_string(),
+            })
+            .collect()
+    }
+
+    /// Builds the pointer to `token` below `parent`.
+    fn pointer(&mut self, parent: Option<usize>, token: Token<'b>) -> JsonPointer {
+        let path = self.push(parent, token);
+        self.resolve(path)
+    }
+}
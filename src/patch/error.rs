@@ -0,0 +1,39 @@
+use thiserror::Error;
+
+use crate::parser::ValueKind;
+use crate::pointer::PointerError;
+
+/// Errors from reading or applying an RFC 6902 json patch. Operations are
+/// numbered from 0 in the order they appear in the patch.
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum PatchError {
+    #[error("Json patches must be an array of operations, found {0}")]
+    NotAnArray(ValueKind),
+
+    #[error("Operation {index} is invalid: {message}")]
+    InvalidOperation { index: usize, message: String },
+
+    #[error("Operation {index} ({op}) failed: {source}")]
+    Pointer {
+        index: usize,
+        op: &'static str,
+        source: PointerError,
+    },
+
+    #[error("Operation {index} (test) failed: the value at {path:?} is not {expected}")]
+    TestFailed {
+        index: usize,
+        path: String,
+        expected: String,
+    },
+
+    #[error("Operation {index} ({op}) cannot remove the whole document")]
+    RemoveRoot { index: usize, op: &'static str },
+
+    #[error("Operation {index} (move) cannot move {from:?} into its own child {path:?}")]
+    MoveIntoChild {
+        index: usize,
+        from: String,
+        path: String,
+    },
+}
@@ -0,0 +1,52 @@
+use crate::parser::{Object, Value};
+
+impl<'a> Value<'a> {
+    /// Applies an RFC 7386 json merge patch. An object patch merges into
+    /// the value member by member, where `null` removes a member and nested
+    /// objects merge recursively, and any other patch replaces the value.
+    ///
+    /// Unlike a [`JsonPatch`](super::JsonPatch), a merge patch cannot set a
+    /// member to `null` or change individual array elements.
+    pub fn merge_patch(&mut self, patch: &Value<'a>) {
+        let mut stack = vec![(self, patch)];
+
+        while let Some((target, patch)) = stack.pop() {
+            let Value::Object(patch) = patch else {
+                *target = patch.clone();
+                continue;
+            };
+
+            if !matches!(target, Value::Object(_)) {
+                *target = Value::Object(Object::new());
+            }
+
+            let Value::Object(object) = target else {
+                unreachable!("target was replaced by an object above")
+            };
+
+            for (key, value) in patch {
+                match value {
+                    Value::Null => {
+                        object.remove(key);
+                    }
+                    Value::Object(_) => {
+                        if !matches!(object.get(key), Some(Value::Object(_))) {
+                            object.insert(key.to_string(), Value::Object(Object::new()));
+                        }
+                    }
+                    value => {
+                        object.insert(key.to_string(), value.clone());
+                    }
+                }
+            }
+
+            // Every nested object patch now has an object member to merge
+            // into, and those members can be borrowed mutably all at once.
+            for (key, member) in object.iter_mut() {
+                if let Some(nested @ Value::Object(_)) = patch.get(key) {
+                    stack.push((member, nested));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,347 @@
+mod diff;
+pub mod error;
+mod merge;
+
+pub use error::PatchError;
+
+use std::fmt;
+
+use crate::parser::{Object, OwnedValue, Value};
+use crate::pointer::{JsonPointer, PointerError};
+
+/// An RFC 6902 json patch: a sequence of operations that transform one
+/// document into another.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct JsonPatch {
+    operations: Vec<PatchOperation>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum PatchOperation {
+    /// Inserts into an array, or sets an object member or the whole document.
+    Add {
+        path: JsonPointer,
+        value: OwnedValue,
+    },
+    Remove {
+        path: JsonPointer,
+    },
+    /// Like `Add`, but the target must already exist.
+    Replace {
+        path: JsonPointer,
+        value: OwnedValue,
+    },
+    Move {
+        from: JsonPointer,
+        path: JsonPointer,
+    },
+    Copy {
+        from: JsonPointer,
+        path: JsonPointer,
+    },
+    /// Fails the patch unless the target equals `value`.
+    Test {
+        path: JsonPointer,
+        value: OwnedValue,
+    },
+}
+
+impl JsonPatch {
+    pub fn new(operations: Vec<PatchOperation>) -> Self {
+        Self { operations }
+    }
+
+    pub fn operations(&self) -> &[PatchOperation] {
+        &self.operations
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.operations.is_empty()
+    }
+
+    /// Reads a patch document, an array of objects such as
+    /// `{"op": "add", "path": "/a", "value": 1}`. Unknown members of an
+    /// operation are ignored.
+    pub fn from_value(value: &Value<'_>) -> Result<Self, PatchError> {
+        let Value::Array(array) = value else {
+            return Err(PatchError::NotAnArray(value.kind()));
+        };
+
+        let operations = array
+            .iter()
+            .enumerate()
+            .map(|(index, operation)| PatchOperation::from_value(index, operation))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self { operations })
+    }
+
+    pub fn to_value(&self) -> OwnedValue {
+        Value::Array(
+            self.operations
+                .iter()
+                .map(PatchOperation::to_value)
+                .collect(),
+        )
+    }
+
+    /// Applies every operation in order. If any of them fails the document
+    /// is left exactly as it was.
+    pub fn apply(&self, document: &mut Value<'_>) -> Result<(), PatchError> {
+        // Operations run on a copy that only replaces the document once all
+        // of them succeed, since undoing a removal would not restore the
+        // member's position within its object.
+        let mut patched = document.clone();
+
+        for (index, operation) in self.operations.iter().enumerate() {
+            operation.apply(index, &mut patched)?;
+        }
+
+        *document = patched;
+        Ok(())
+    }
+
+    /// Generates a patch that transforms `from` into `to`, see [`diff`].
+    pub fn diff(from: &Value<'_>, to: &Value<'_>) -> Self {
+        let mut operations = Vec::new();
+        diff::diff(from, to, &mut operations);
+
+        Self { operations }
+    }
+}
+
+impl From<Vec<PatchOperation>> for JsonPatch {
+    fn from(operations: Vec<PatchOperation>) -> Self {
+        Self::new(operations)
+    }
+}
+
+/// Formats the patch as a json document, pretty printed with `{:#}`.
+impl fmt::Display for JsonPatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.to_value(), f)
+    }
+}
+
+impl PatchOperation {
+    /// The name of the operation as written in the `op` member.
+    pub fn name(&self) -> &'static str {
+        match self {
+            PatchOperation::Add { .. } => "add",
+            PatchOperation::Remove { .. } => "remove",
+            PatchOperation::Replace { .. } => "replace",
+            PatchOperation::Move { .. } => "move",
+            PatchOperation::Copy { .. } => "copy",
+            PatchOperation::Test { .. } => "test",
+        }
+    }
+
+    /// The location the operation changes or tests.
+    pub fn path(&self) -> &JsonPointer {
+        match self {
+            PatchOperation::Add { path, .. }
+            | PatchOperation::Remove { path }
+            | PatchOperation::Replace { path, .. }
+            | PatchOperation::Move { path, .. }
+            | PatchOperation::Copy { path, .. }
+            | PatchOperation::Test { path, .. } => path,
+        }
+    }
+
+    fn from_value(index: usize, operation: &Value<'_>) -> Result<Self, PatchError> {
+        let invalid = |message: String| PatchError::InvalidOperation { index, message };
+
+        let Value::Object(object) = operation else {
+            return Err(invalid(format!(
+                "expected an object, found {}",
+                operation.kind()
+            )));
+        };
+
+        let pointer = |member: &str| -> Result<JsonPointer, PatchError> {
+            match object.get(member) {
+                Some(Value::String(s)) => s.parse().map_err(|e| invalid(format!("{e}"))),
+                Some(other) => Err(invalid(format!(
+                    "{member:?} must be a string, found {}",
+                    other.kind()
+                ))),
+                None => Err(invalid(format!("missing {member:?}"))),
+            }
+        };
+        let value = || -> Result<OwnedValue, PatchError> {
+            object
+                .get("value")
+                .map(|value| value.clone().into_owned())
+                .ok_or_else(|| invalid("missing \"value\"".to_string()))
+        };
+
+        let operation = match object.get("op") {
+            Some(Value::String(op)) => match op.as_ref() {
+                "add" => PatchOperation::Add {
+                    path: pointer("path")?,
+                    value: value()?,
+                },
+                "remove" => PatchOperation::Remove {
+                    path: pointer("path")?,
+                },
+                "replace" => PatchOperation::Replace {
+                    path: pointer("path")?,
+                    value: value()?,
+                },
+                "move" => PatchOperation::Move {
+                    from: pointer("from")?,
+                    path: pointer("path")?,
+                },
+                "copy" => PatchOperation::Copy {
+                    from: pointer("from")?,
+                    path: pointer("path")?,
+                },
+                "test" => PatchOperation::Test {
+                    path: pointer("path")?,
+                    value: value()?,
+                },
+                other => return Err(invalid(format!("unknown op {other:?}"))),
+            },
+            Some(other) => {
+                return Err(invalid(format!(
+                    "\"op\" must be a string, found {}",
+                    other.kind()
+                )));
+            }
+            None => return Err(invalid("missing \"op\"".to_string())),
+        };
+
+        Ok(operation)
+    }
+
+    fn to_value(&self) -> OwnedValue {
+        let pointer = |pointer: &JsonPointer| Value::String(pointer.to_string().into());
+
+        let mut object = Object::new();
+        object.insert("op", Value::String(self.name().into()));
+
+        if let PatchOperation::Move { from, .. } | PatchOperation::Copy { from, .. } = self {
+            object.insert("from", pointer(from));
+        }
+
+        object.insert("path", pointer(self.path()));
+
+        if let PatchOperation::Add { value, .. }
+        | PatchOperation::Replace { value, .. }
+        | PatchOperation::Test { value, .. } = self
+        {
+            object.insert("value", value.clone());
+        }
+
+        Value::Object(object)
+    }
+
+    fn apply(&self, index: usize, document: &mut Value<'_>) -> Result<(), PatchError> {
+        let op = self.name();
+        let failed = |source| PatchError::Pointer { index, op, source };
+
+        match self {
+            PatchOperation::Add { path, value } => {
+                add(document, path, value.clone()).map_err(failed)?;
+            }
+            PatchOperation::Remove { path } => {
+                remove(document, path, index, op)?;
+            }
+            PatchOperation::Replace { path, value } => {
+                *path.resolve_mut(document).map_err(failed)? = value.clone();
+            }
+            PatchOperation::Move { from, path } => {
+                // Resolve first so that moving a missing value to itself fails.
+                from.resolve(document).map_err(failed)?;
+
+                if from == path {
+                    return Ok(());
+                }
+
+                if path.tokens().starts_with(from.tokens()) {
+                    return Err(PatchError::MoveIntoChild {
+                        index,
+                        from: from.to_string(),
+                        path: path.to_string(),
+                    });
+                }
+
+                let value = remove(document, from, index, op)?;
+                add(document, path, value).map_err(failed)?;
+            }
+            PatchOperation::Copy { from, path } => {
+                let value = from.resolve(document).map_err(failed)?.clone();
+                add(document, path, value).map_err(failed)?;
+            }
+            PatchOperation::Test { path, value } => {
+                if path.resolve(document).map_err(failed)? != value {
+                    return Err(PatchError::TestFailed {
+                        index,
+                        path: path.to_string(),
+                        expected: value.to_string(),
+                    });
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Sets the object member or inserts the array element at `path`, where the
+/// token `-` appends to an array.
+fn add<'a>(
+    document: &mut Value<'a>,
+    path: &JsonPointer,
+    value: Value<'a>,
+) -> Result<(), PointerError> {
+    let Some((parent, token)) = path.split_last() else {
+        *document = value;
+        return Ok(());
+    };
+    let last = path.tokens().len() - 1;
+
+    match parent.resolve_mut(document)? {
+        Value::Object(object) => {
+            object.insert(token.to_string(), value);
+        }
+        Value::Array(array) if token == "-" => array.push(value),
+        Value::Array(array) => {
+            // The index one past the end is allowed and appends.
+            let index = path.array_index(last, array.len() + 1)?;
+            array.insert(index, value);
+        }
+        other => return Err(path.not_a_container(last, other)),
+    }
+
+    Ok(())
+}
+
+/// Removes and returns the value at `path`.
+fn remove<'a>(
+    document: &mut Value<'a>,
+    path: &JsonPointer,
+    index: usize,
+    op: &'static str,
+) -> Result<Value<'a>, PatchError> {
+    let failed = |source| PatchError::Pointer { index, op, source };
+
+    let Some((parent, token)) = path.split_last() else {
+        return Err(PatchError::RemoveRoot { index, op });
+    };
+    let last = path.tokens().len() - 1;
+
+    match parent.resolve_mut(document).map_err(failed)? {
+        Value::Object(object) => object
+            .remove(token)
+            .ok_or_else(|| failed(path.missing_key(last))),
+        Value::Array(array) => {
+            let i = path.array_index(last, array.len()).map_err(failed)?;
+            Ok(array.remove(i))
+        }
+        other => Err(failed(path.not_a_container(last, other))),
+    }
+}
+
+#[cfg(test)]
+mod test;
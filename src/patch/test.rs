@@ -0,0 +1,372 @@
+use super::*;
+use crate::parser::{DEEP, ValueKind, parse, parse_deep};
+use crate::pointer::PointerError;
+
+fn patch(operations: &str) -> JsonPatch {
+    JsonPatch::from_value(&parse(operations)).expect("Invalid patch")
+}
+
+/// Applies `operations` to `document` and returns the result.
+fn apply(document: &str, operations: &str) -> Result<OwnedValue, PatchError> {
+    let mut document = parse(document).into_owned();
+    patch(operations).apply(&mut document)?;
+
+    Ok(document)
+}
+
+#[test]
+fn rfc_examples() {
+    let cases = [
+        // A.1 to A.4
+        (
+            r#"{"foo": "bar"}"#,
+            r#"[{"op": "add", "path": "/baz", "value": "qux"}]"#,
+            r#"{"baz": "qux", "foo": "bar"}"#,
+        ),
+        (
+            r#"{"foo": ["bar", "baz"]}"#,
+            r#"[{"op": "add", "path": "/foo/1", "value": "qux"}]"#,
+            r#"{"foo": ["bar", "qux", "baz"]}"#,
+        ),
+        (
+            r#"{"baz": "qux", "foo": "bar"}"#,
+            r#"[{"op": "remove", "path": "/baz"}]"#,
+            r#"{"foo": "bar"}"#,
+        ),
+        (
+            r#"{"foo": ["bar", "qux", "baz"]}"#,
+            r#"[{"op": "remove", "path": "/foo/1"}]"#,
+            r#"{"foo": ["bar", "baz"]}"#,
+        ),
+        // A.5 to A.7
+        (
+            r#"{"baz": "qux", "foo": "bar"}"#,
+            r#"[{"op": "replace", "path": "/baz", "value": "boo"}]"#,
+            r#"{"baz": "boo", "foo": "bar"}"#,
+        ),
+        (
+            r#"{"foo": {"bar": "baz", "waldo": "fred"}, "qux": {"corge": "grault"}}"#,
+            r#"[{"op": "move", "from": "/foo/waldo", "path": "/qux/thud"}]"#,
+            r#"{"foo": {"bar": "baz"}, "qux": {"corge": "grault", "thud": "fred"}}"#,
+        ),
+        (
+            r#"{"foo": ["all", "grass", "cows", "eat"]}"#,
+            r#"[{"op": "move", "from": "/foo/1", "path": "/foo/3"}]"#,
+            r#"{"foo": ["all", "cows", "eat", "grass"]}"#,
+        ),
+        // A.8, A.10, A.14 and A.16
+        (
+            r#"{"baz": "qux", "foo": ["a", 2, "c"]}"#,
+            r#"[{"op": "test", "path": "/baz", "value": "qux"}, {"op": "test", "path": "/foo/1", "value": 2}]"#,
+            r#"{"baz": "qux", "foo": ["a", 2, "c"]}"#,
+        ),
+        (
+            r#"{"foo": "bar"}"#,
+            r#"[{"op": "add", "path": "/child", "value": {"grandchild": {}}}]"#,
+            r#"{"foo": "bar", "child": {"grandchild": {}}}"#,
+        ),
+        (
+            r#"{"/": 9, "~1": 10}"#,
+            r#"[{"op": "test", "path": "/~01", "value": 10}]"#,
+            r#"{"/": 9, "~1": 10}"#,
+        ),
+        (
+            r#"{"foo": ["bar"]}"#,
+            r#"[{"op": "add", "path": "/foo/-", "value": ["abc", "def"]}]"#,
+            r#"{"foo": ["bar", ["abc", "def"]]}"#,
+        ),
+    ];
+
+    for (document, operations, expected) in cases {
+        assert_eq!(
+            apply(document, operations),
+            Ok(parse(expected)),
+            "{operations}"
+        );
+    }
+}
+
+#[test]
+fn copy_and_root_operations() {
+    assert_eq!(
+        apply(
+            r#"{"a": {"b": 1}}"#,
+            r#"[{"op": "copy", "from": "/a", "path": "/c"}, {"op": "replace", "path": "/c/b", "value": 2}]"#
+        ),
+        Ok(parse(r#"{"a": {"b": 1}, "c": {"b": 2}}"#))
+    );
+    assert_eq!(
+        apply("[1]", r#"[{"op": "add", "path": "", "value": {"x": 1}}]"#),
+        Ok(parse(r#"{"x": 1}"#))
+    );
+    assert_eq!(
+        apply(
+            r#"{"a": 1}"#,
+            r#"[{"op": "move", "from": "/a", "path": "/a"}]"#
+        ),
+        Ok(parse(r#"{"a": 1}"#))
+    );
+}
+
+#[test]
+fn failed_operations() {
+    assert_eq!(
+        apply(
+            r#"{"baz": "qux"}"#,
+            r#"[{"op": "test", "path": "/baz", "value": "bar"}]"#
+        ),
+        Err(PatchError::TestFailed {
+            index: 0,
+            path: "/baz".to_string(),
+            expected: r#""bar""#.to_string(),
+        })
+    );
+    assert_eq!(
+        apply(
+            r#"{"foo": "bar"}"#,
+            r#"[{"op": "add", "path": "/baz/bat", "value": 1}]"#
+        ),
+        Err(PatchError::Pointer {
+            index: 0,
+            op: "add",
+            source: PointerError::MissingKey {
+                parent: String::new(),
+                key: "baz".to_string(),
+            },
+        })
+    );
+    assert_eq!(
+        apply(
+            r#"{"foo": [1]}"#,
+            r#"[{"op": "add", "path": "/foo/2", "value": 1}]"#
+        ),
+        Err(PatchError::Pointer {
+            index: 0,
+            op: "add",
+            source: PointerError::IndexOutOfRange {
+                parent: "/foo".to_string(),
+                index: "2".to_string(),
+                len: 2,
+            },
+        })
+    );
+    assert_eq!(
+        apply("[1]", r#"[{"op": "remove", "path": ""}]"#),
+        Err(PatchError::RemoveRoot {
+            index: 0,
+            op: "remove"
+        })
+    );
+    assert_eq!(
+        apply(
+            r#"{"a": {}}"#,
+            r#"[{"op": "move", "from": "/a", "path": "/a/b"}]"#
+        ),
+        Err(PatchError::MoveIntoChild {
+            index: 0,
+            from: "/a".to_string(),
+            path: "/a/b".to_string(),
+        })
+    );
+}
+
+#[test]
+fn failed_patches_leave_the_document_unchanged() {
+    let mut document = parse(r#"{"a": 1, "b": [1, 2], "c": 3}"#);
+    let before = document.clone();
+
+    let result = patch(
+        r#"[
+            {"op": "remove", "path": "/a"},
+            {"op": "add", "path": "/b/0", "value": 0},
+            {"op": "test", "path": "/c", "value": 4}
+        ]"#,
+    )
+    .apply(&mut document);
+
+    assert!(matches!(
+        result,
+        Err(PatchError::TestFailed { index: 2, .. })
+    ));
+    assert_eq!(document, before);
+    assert_eq!(document.as_object().unwrap().keys().next(), Some("a"));
+}
+
+#[test]
+fn invalid_patch_documents() {
+    let error = |operations: &str| JsonPatch::from_value(&parse(operations)).unwrap_err();
+
+    assert_eq!(error("{}"), PatchError::NotAnArray(ValueKind::Object));
+    assert_eq!(
+        error(r#"[{"op": "add", "path": "/a", "value": 1}, {"op": "frob", "path": ""}]"#),
+        PatchError::InvalidOperation {
+            index: 1,
+            message: r#"unknown op "frob""#.to_string(),
+        }
+    );
+    assert_eq!(
+        error(r#"[{"op": "add", "path": "/a"}]"#),
+        PatchError::InvalidOperation {
+            index: 0,
+            message: r#"missing "value""#.to_string(),
+        }
+    );
+    assert_eq!(
+        error(r#"[{"op": "copy", "path": "a", "from": "/b"}]"#),
+        PatchError::InvalidOperation {
+            index: 0,
+            message: r#"Json pointers must be empty or start with '/', found "a""#.to_string(),
+        }
+    );
+}
+
+#[test]
+fn patches_round_trip_through_json() {
+    let operations =
+        r#"[{"op":"move","from":"/a~1b","path":"/c"},{"op":"test","path":"/c","value":[null]}]"#;
+
+    assert_eq!(patch(operations).to_string(), operations);
+}
+
+#[test]
+fn diff_generates_minimal_patches() {
+    let diff = |from: &str, to: &str| JsonPatch::diff(&parse(from), &parse(to)).to_string();
+
+    assert_eq!(
+        diff(r#"{"a": [1, {"b": 2}]}"#, r#"{"a": [1, {"b": 2}]}"#),
+        "[]"
+    );
+    assert_eq!(
+        diff(
+            r#"{"a": 1, "b": {"c": 2, "d": 3}}"#,
+            r#"{"b": {"c": 2, "d": 4}, "e": 5}"#
+        ),
+        r#"[{"op":"remove","path":"/a"},{"op":"replace","path":"/b/d","value":4},{"op":"add","path":"/e","value":5}]"#
+    );
+    assert_eq!(
+        diff("[1, 2, 3, 4]", "[1, 2, 9, 3, 4]"),
+        r#"[{"op":"add","path":"/2","value":9}]"#
+    );
+    assert_eq!(
+        diff("[1, 2, 3, 4]", "[1, 4]"),
+        r#"[{"op":"remove","path":"/2"},{"op":"remove","path":"/1"}]"#
+    );
+    assert_eq!(
+        diff(r#"{"a": [1]}"#, r#"{"a": "x"}"#),
+        r#"[{"op":"replace","path":"/a","value":"x"}]"#
+    );
+    assert_eq!(diff("1", "2"), r#"[{"op":"replace","path":"","value":2}]"#);
+}
+
+#[test]
+fn diff_patches_transform_the_first_document_into_the_second() {
+    let pairs = [
+        (
+            r#"{"a": [1, 2, {"x": 1}], "b": null}"#,
+            r#"{"a": [0, 2, {"y": 1}, 5], "c": true}"#,
+        ),
+        ("[1, 2, 3, 4, 5]", "[5, 4]"),
+        (r#"[{"a/b": 1}, [], "s"]"#, r#"[{"a/b": 2, "~": 3}, [[]]]"#),
+        (r#"{"k": {}}"#, "[]"),
+    ];
+
+    for (from, to) in pairs {
+        let mut document = parse(from);
+        JsonPatch::diff(&document, &parse(to))
+            .apply(&mut document)
+            .expect("Generated patch failed");
+
+        assert_eq!(document, parse(to), "{from} -> {to}");
+    }
+}
+
+#[test]
+fn merge_patch_rfc_examples() {
+    // RFC 7386 appendix A
+    let cases = [
+        (r#"{"a":"b"}"#, r#"{"a":"c"}"#, r#"{"a":"c"}"#),
+        (r#"{"a":"b"}"#, r#"{"b":"c"}"#, r#"{"a":"b","b":"c"}"#),
+        (r#"{"a":"b"}"#, r#"{"a":null}"#, r#"{}"#),
+        (r#"{"a":"b","b":"c"}"#, r#"{"a":null}"#, r#"{"b":"c"}"#),
+        (r#"{"a":["b"]}"#, r#"{"a":"c"}"#, r#"{"a":"c"}"#),
+        (r#"{"a":"c"}"#, r#"{"a":["b"]}"#, r#"{"a":["b"]}"#),
+        (
+            r#"{"a":{"b":"c"}}"#,
+            r#"{"a":{"b":"d","c":null}}"#,
+            r#"{"a":{"b":"d"}}"#,
+        ),
+        (r#"{"a":[{"b":"c"}]}"#, r#"{"a":[1]}"#, r#"{"a":[1]}"#),
+        (r#"["a","b"]"#, r#"["c","d"]"#, r#"["c","d"]"#),
+        (r#"{"a":"b"}"#, r#"["c"]"#, r#"["c"]"#),
+        (r#"{"a":"foo"}"#, "null", "null"),
+        (r#"{"a":"foo"}"#, r#""bar""#, r#""bar""#),
+        (r#"{"e":null}"#, r#"{"a":1}"#, r#"{"e":null,"a":1}"#),
+        (r#"[1,2]"#, r#"{"a":"b","c":null}"#, r#"{"a":"b"}"#),
+        (
+            r#"{}"#,
+            r#"{"a":{"bb":{"ccc":null}}}"#,
+            r#"{"a":{"bb":{}}}"#,
+        ),
+    ];
+
+    for (target, patch, expected) in cases {
+        let mut value = parse(target);
+        value.merge_patch(&parse(patch));
+
+        assert_eq!(value, parse(expected), "{target} merged with {patch}");
+    }
+}
+
+#[test]
+fn merge_patch_keeps_member_order() {
+    let mut value = parse(r#"{"a": 1, "b": {"x": 1, "y": 2}, "c": 3}"#);
+    value.merge_patch(&parse(r#"{"d": 4, "b": {"x": null, "z": 3}, "a": 0}"#));
+
+    assert_eq!(
+        value.to_string(),
+        r#"{"a":0,"b":{"y":2,"z":3},"c":3,"d":4}"#
+    );
+}
+
+#[test]
+fn merge_patch_handles_very_deep_patches() {
+    let patch = parse_deep(r#"{"a":"#, "null");
+
+    let mut value = Value::Null;
+    value.merge_patch(&patch);
+
+    let mut current = &value;
+    for _ in 1..DEEP {
+        current = &current["a"];
+    }
+
+    assert_eq!(current.as_object().map(Object::len), Some(0));
+}
+
+#[test]
+fn apply_and_diff_handle_very_deep_documents() {
+    let document = parse_deep("[", "");
+
+    let mut patched = document.clone();
+    patch(r#"[{"op": "test", "path": "/0/0", "value": [[]]}, {"op": "add", "path": "/-", "value": 1}]"#)
+        .apply(&mut patched)
+        .expect_err("the test fails");
+    assert_eq!(patched, document);
+
+    patch(r#"[{"op": "add", "path": "/-", "value": 1}]"#)
+        .apply(&mut patched)
+        .unwrap();
+    assert_eq!(JsonPatch::diff(&document, &document), JsonPatch::default());
+    assert_eq!(
+        JsonPatch::diff(&document, &patched).to_string(),
+        r#"[{"op":"add","path":"/1","value":1}]"#
+    );
+
+    // Differing only at the bottom used to take time quadratic in the depth.
+    let other = parse_deep("[", "1");
+    let operations = JsonPatch::diff(&document, &other).operations;
+    assert!(matches!(
+        &operations[..],
+        [PatchOperation::Add { path, .. }] if path.tokens().len() == DEEP
+    ));
+}
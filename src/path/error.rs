@@ -0,0 +1,46 @@
+use thiserror::Error;
+
+/// An error in the text of a JSONPath query, located by its 1-based column.
+#[derive(Debug, PartialEq, Eq)]
+pub struct PathError {
+    pub kind: PathErrorKind,
+    pub col: usize,
+}
+
+impl std::fmt::Display for PathError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Error at col {}: {}", self.col, self.kind)
+    }
+}
+
+impl std::error::Error for PathError {}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum PathErrorKind {
+    #[error("JSONPath queries must start with '$'")]
+    MissingRoot,
+
+    #[error("Expected {expected}, found {found}")]
+    Unexpected {
+        expected: &'static str,
+        found: String,
+    },
+
+    #[error("Invalid escape sequence in string literal")]
+    InvalidEscape,
+
+    #[error("Unterminated string literal")]
+    UnterminatedString,
+
+    #[error("Integers must be between -(2^53 - 1) and 2^53 - 1 without leading zeros")]
+    InvalidInteger,
+
+    #[error("Invalid number literal {0:?}")]
+    InvalidNumber(String),
+
+    #[error("Comparisons only accept literals and queries that select at most one node")]
+    NonSingularQuery,
+
+    #[error("Function extensions such as {0}() are not supported")]
+    UnsupportedFunction(String),
+}
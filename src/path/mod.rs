@@ -0,0 +1,459 @@
+pub mod error;
+mod parse;
+
+pub use error::{PathError, PathErrorKind};
+
+use std::cmp::Ordering;
+use std::{fmt, str::FromStr};
+
+use crate::parser::Value;
+use crate::pointer::JsonPointer;
+
+/// A compiled RFC 9535 JSONPath query such as `$.store.book[?@.price < 10].title`.
+///
+/// Supports name, wildcard, index, slice and filter selectors in child and
+/// descendant segments. Filters can test for existence and compare literals
+/// with queries that select at most one node, but function extensions such
+/// as `length()` are not supported.
+#[derive(Clone, Debug, PartialEq)]
+pub struct JsonPath {
+    query: Query,
+}
+
+/// A node selected by a query: a value within the document together with
+/// the normalized path that locates it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Node<'v, 'a> {
+    pub path: NormalizedPath,
+    pub value: &'v Value<'a>,
+}
+
+/// The unique path to a node written with one bracketed selector per level,
+/// as in `$['store']['book'][0]`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct NormalizedPath(Vec<PathElement>);
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum PathElement {
+    Name(String),
+    Index(usize),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Query {
+    /// Whether the query starts at the current node `@` rather than the
+    /// root `$`, which is only possible inside filters.
+    relative: bool,
+    segments: Vec<Segment>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Segment {
+    Child(Vec<Selector>),
+    Descendant(Vec<Selector>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Selector {
+    Name(String),
+    Wildcard,
+    Index(i64),
+    Slice {
+        start: Option<i64>,
+        end: Option<i64>,
+        step: Option<i64>,
+    },
+    Filter(Expr),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Expr {
+    Or(Vec<Expr>),
+    And(Vec<Expr>),
+    Not(Box<Expr>),
+    Exists(Query),
+    Compare(Comparable, Op, Comparable),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Comparable {
+    Literal(Value<'static>),
+    /// A query selecting at most one node, which compares as that node's
+    /// value or as nothing.
+    Query(Query),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Op {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl JsonPath {
+    /// Returns every node the query selects from `root`, in document order
+    /// for each selector.
+    pub fn query<'v, 'a>(&self, root: &'v Value<'a>) -> Vec<Node<'v, 'a>> {
+        let mut paths = Paths::default();
+        let start = Cursor {
+            path: None,
+            value: root,
+        };
+
+        self.query
+            .select(start, root, &mut paths)
+            .into_iter()
+            .map(|cursor| Node {
+                path: paths.resolve(cursor.path),
+                value: cursor.value,
+            })
+            .collect()
+    }
+}
+
+impl FromStr for JsonPath {
+    type Err = PathError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse::parse(s).map(|query| JsonPath { query })
+    }
+}
+
+impl<'a> Value<'a> {
+    /// Evaluates a JSONPath query against this value, see [`JsonPath`].
+    pub fn query(&self, path: &str) -> Result<Vec<Node<'_, 'a>>, PathError> {
+        Ok(path.parse::<JsonPath>()?.query(self))
+    }
+}
+
+/// The paths of every node visited while evaluating a query. Each one is
+/// stored as a step from its parent, so that deep documents do not need a
+/// full copy of the path for every node.
+#[derive(Default)]
+struct Paths<'v>(Vec<(Option<usize>, Step<'v>)>);
+
+#[derive(Clone, Copy)]
+enum Step<'v> {
+    Name(&'v str),
+    Index(usize),
+}
+
+impl<'v> Paths<'v> {
+    fn push(&mut self, parent: Option<usize>, step: Step<'v>) -> Option<usize> {
+        self.0.push((parent, step));
+        Some(self.0.len() - 1)
+    }
+
+    fn resolve(&self, mut path: Option<usize>) -> NormalizedPath {
+        let mut elements = Vec::new();
+
+        while let Some(i) = path {
+            let (parent, step) = self.0[i];
+            elements.push(match step {
+                Step::Name(name) => PathElement::Name(name.to_string()),
+                Step::Index(i) => PathElement::Index(i),
+            });
+            path = parent;
+        }
+
+        elements.reverse();
+        NormalizedPath(elements)
+    }
+}
+
+/// A node during evaluation, whose path is an index into [`Paths`], or
+/// `None` for the root.
+#[derive(Clone, Copy)]
+struct Cursor<'v, 'a> {
+    path: Option<usize>,
+    value: &'v Value<'a>,
+}
+
+impl<'v, 'a> Cursor<'v, 'a> {
+    fn child(self, step: Step<'v>, value: &'v Value<'a>, paths: &mut Paths<'v>) -> Self {
+        Cursor {
+            path: paths.push(self.path, step),
+            value,
+        }
+    }
+
+    /// Returns every array element or object member value, in order.
+    fn children(self, paths: &mut Paths<'v>) -> Vec<Self> {
+        match self.value {
+            Value::Array(array) => array
+                .iter()
+                .enumerate()
+                .map(|(i, value)| self.child(Step::Index(i), value, paths))
+                .collect(),
+            Value::Object(object) => object
+                .iter()
+                .map(|(key, value)| self.child(Step::Name(key), value, paths))
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+impl Query {
+    /// Whether the query can select at most one node, because it only uses
+    /// name and index selectors in child segments.
+    fn is_singular(&self) -> bool {
+        self.segments.iter().all(|segment| {
+            matches!(segment, Segment::Child(selectors)
+                if matches!(selectors.as_slice(), [Selector::Name(_) | Selector::Index(_)]))
+        })
+    }
+
+    /// Applies the segments in turn, starting from `current` for relative
+    /// queries and from `root` otherwise.
+    fn select<'v, 'a>(
+        &self,
+        current: Cursor<'v, 'a>,
+        root: &'v Value<'a>,
+        paths: &mut Paths<'v>,
+    ) -> Vec<Cursor<'v, 'a>> {
+        let start = if self.relative {
+            current
+        } else {
+            Cursor {
+                path: None,
+                value: root,
+            }
+        };
+
+        let mut nodes = vec![start];
+
+        for segment in &self.segments {
+            let mut selected = Vec::new();
+
+            for node in nodes {
+                match segment {
+                    Segment::Child(selectors) => {
+                        select_children(node, selectors, root, paths, &mut selected);
+                    }
+                    Segment::Descendant(selectors) => {
+                        for descendant in descendants(node, paths) {
+                            select_children(descendant, selectors, root, paths, &mut selected);
+                        }
+                    }
+                }
+            }
+
+            nodes = selected;
+        }
+
+        nodes
+    }
+}
+
+/// Returns `node` followed by all of its descendants, each before its own
+/// children.
+fn descendants<'v, 'a>(node: Cursor<'v, 'a>, paths: &mut Paths<'v>) -> Vec<Cursor<'v, 'a>> {
+    let mut visited = Vec::new();
+    let mut stack = vec![node];
+
+    while let Some(node) = stack.pop() {
+        stack.extend(node.children(paths).into_iter().rev());
+        visited.push(node);
+    }
+
+    visited
+}
+
+/// Applies every selector to `node`, appending what they select to `out`.
+fn select_children<'v, 'a>(
+    node: Cursor<'v, 'a>,
+    selectors: &[Selector],
+    root: &'v Value<'a>,
+    paths: &mut Paths<'v>,
+    out: &mut Vec<Cursor<'v, 'a>>,
+) {
+    for selector in selectors {
+        match (selector, node.value) {
+            (Selector::Name(name), Value::Object(object)) => {
+                if let Some((key, value)) = object.get_key_value(name) {
+                    out.push(node.child(Step::Name(key), value, paths));
+                }
+            }
+            (Selector::Wildcard, _) => out.extend(node.children(paths)),
+            (Selector::Index(index), Value::Array(array)) => {
+                if let Some(i) = normalize_index(*index, array.len()) {
+                    out.push(node.child(Step::Index(i), &array[i], paths));
+                }
+            }
+            (Selector::Slice { start, end, step }, Value::Array(array)) => {
+                for i in slice_indices(*start, *end, step.unwrap_or(1), array.len()) {
+                    out.push(node.child(Step::Index(i), &array[i], paths));
+                }
+            }
+            (Selector::Filter(expr), _) => {
+                for child in node.children(paths) {
+                    if expr.test(child, root, paths) {
+                        out.push(child);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Converts a possibly negative index into a position within `len`.
+fn normalize_index(index: i64, len: usize) -> Option<usize> {
+    let len = len as i64;
+    let index = if index < 0 { len + index } else { index };
+
+    (0..len).contains(&index).then_some(index as usize)
+}
+
+/// Returns the indices selected by `[start:end:step]` from an array of
+/// length `len`, following section 2.3.4.2.2 of RFC 9535.
+fn slice_indices(start: Option<i64>, end: Option<i64>, step: i64, len: usize) -> Vec<usize> {
+    let len = len as i64;
+    let normalize = |i: i64| if i < 0 { len + i } else { i };
+    let mut indices = Vec::new();
+
+    if step > 0 {
+        let lower = normalize(start.unwrap_or(0)).clamp(0, len);
+        let upper = normalize(end.unwrap_or(len)).clamp(0, len);
+        let mut i = lower;
+
+        while i < upper {
+            indices.push(i as usize);
+            i += step;
+        }
+    } else if step < 0 {
+        let upper = normalize(start.unwrap_or(len - 1)).clamp(-1, len - 1);
+        let lower = normalize(end.unwrap_or(-len - 1)).clamp(-1, len - 1);
+        let mut i = upper;
+
+        while lower < i {
+            indices.push(i as usize);
+            i += step;
+        }
+    }
+
+    indices
+}
+
+impl Expr {
+    fn test<'v, 'a>(
+        &self,
+        current: Cursor<'v, 'a>,
+        root: &'v Value<'a>,
+        paths: &mut Paths<'v>,
+    ) -> bool {
+        match self {
+            Expr::Or(operands) => operands.iter().any(|expr| expr.test(current, root, paths)),
+            Expr::And(operands) => operands.iter().all(|expr| expr.test(current, root, paths)),
+            Expr::Not(expr) => !expr.test(current, root, paths),
+            Expr::Exists(query) => !query.select(current, root, paths).is_empty(),
+            Expr::Compare(left, op, right) => {
+                let left = left.evaluate(current, root, paths);
+                let right = right.evaluate(current, root, paths);
+
+                match op {
+                    Op::Eq => equal(left, right),
+                    Op::Ne => !equal(left, right),
+                    Op::Lt => less(left, right),
+                    Op::Le => less(left, right) || equal(left, right),
+                    Op::Gt => less(right, left),
+                    Op::Ge => less(right, left) || equal(left, right),
+                }
+            }
+        }
+    }
+}
+
+impl Comparable {
+    /// Returns the value to compare, or `None` if a query selected nothing.
+    fn evaluate<'c, 'v: 'c, 'a>(
+        &'c self,
+        current: Cursor<'v, 'a>,
+        root: &'v Value<'a>,
+        paths: &mut Paths<'v>,
+    ) -> Option<&'c Value<'a>> {
+        match self {
+            Comparable::Literal(value) => Some(value),
+            Comparable::Query(query) => query
+                .select(current, root, paths)
+                .first()
+                .map(|node| node.value),
+        }
+    }
+}
+
+/// Two missing values are equal, and otherwise values are equal if they
+/// have the same type and contents, with numbers compared numerically.
+fn equal(left: Option<&Value<'_>>, right: Option<&Value<'_>>) -> bool {
+    left == right
+}
+
+/// Only numbers and strings are ordered. Strings compare by Unicode scalar
+/// values, which matches comparing their UTF-8 bytes.
+fn less(left: Option<&Value<'_>>, right: Option<&Value<'_>>) -> bool {
+    let ordering = match (left, right) {
+        (Some(Value::Number(a)), Some(Value::Number(b))) => a.cmp(b),
+        (Some(Value::String(a)), Some(Value::String(b))) => a.cmp(b),
+        _ => return false,
+    };
+
+    ordering == Ordering::Less
+}
+
+impl NormalizedPath {
+    pub fn elements(&self) -> &[PathElement] {
+        &self.0
+    }
+
+    /// Converts the path into the json pointer addressing the same node.
+    pub fn to_pointer(&self) -> JsonPointer {
+        self.0
+            .iter()
+            .fold(JsonPointer::root(), |pointer, element| match element {
+                PathElement::Name(name) => pointer.join(name.as_str()),
+                PathElement::Index(i) => pointer.join(i.to_string()),
+            })
+    }
+}
+
+/// Writes names in single quotes with the escapes required by section 2.7
+/// of RFC 9535.
+impl fmt::Display for NormalizedPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("$")?;
+
+        for element in &self.0 {
+            match element {
+                PathElement::Index(i) => write!(f, "[{i}]")?,
+                PathElement::Name(name) => {
+                    f.write_str("['")?;
+
+                    for c in name.chars() {
+                        match c {
+                            '\'' => f.write_str("\\'")?,
+                            '\\' => f.write_str("\\\\")?,
+                            '\u{8}' => f.write_str("\\b")?,
+                            '\u{c}' => f.write_str("\\f")?,
+                            '\n' => f.write_str("\\n")?,
+                            '\r' => f.write_str("\\r")?,
+                            '\t' => f.write_str("\\t")?,
+                            c if c < ' ' => write!(f, "\\u{:04x}", c as u32)?,
+                            c => write!(f, "{c}")?,
+                        }
+                    }
+
+                    f.write_str("']")?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,489 @@
+use std::borrow::Cow;
+
+use super::error::{PathError, PathErrorKind};
+use super::{Comparable, Expr, Op, Query, Segment, Selector};
+use crate::parser::{Number, Value};
+
+/// Largest integer allowed in indices and slices, so that every index is
+/// exactly representable as an I-JSON number.
+const MAX_INTEGER: i64 = (1 << 53) - 1;
+
+type Result<T> = std::result::Result<T, PathError>;
+
+/// Parses a complete JSONPath query such as `$.store.book[?@.price < 10]`.
+pub(super) fn parse(query: &str) -> Result<Query> {
+    let mut parser = QueryParser {
+        input: query,
+        pos: 0,
+    };
+
+    if !parser.eat('$') {
+        return Err(parser.error(PathErrorKind::MissingRoot));
+    }
+
+    let segments = parser.parse_segments()?;
+
+    if parser.peek().is_some() {
+        return Err(parser.unexpected("a segment"));
+    }
+
+    Ok(Query {
+        relative: false,
+        segments,
+    })
+}
+
+struct QueryParser<'q> {
+    input: &'q str,
+    /// Byte offset of the next character.
+    pos: usize,
+}
+
+impl<'q> QueryParser<'q> {
+    fn parse_segments(&mut self) -> Result<Vec<Segment>> {
+        let mut segments = Vec::new();
+
+        loop {
+            let start = self.pos;
+            self.skip_blank();
+
+            let segment = if self.eat_str("..") {
+                match self.peek() {
+                    Some('[') => Segment::Descendant(self.parse_bracketed()?),
+                    Some('*') => {
+                        self.bump();
+                        Segment::Descendant(vec![Selector::Wildcard])
+                    }
+                    _ => Segment::Descendant(vec![Selector::Name(self.parse_member_name()?)]),
+                }
+            } else if self.eat('.') {
+                if self.eat('*') {
+                    Segment::Child(vec![Selector::Wildcard])
+                } else {
+                    Segment::Child(vec![Selector::Name(self.parse_member_name()?)])
+                }
+            } else if self.peek() == Some('[') {
+                Segment::Child(self.parse_bracketed()?)
+            } else {
+                // Blank space is only allowed between segments, so leave it
+                // for whatever follows the query.
+                self.pos = start;
+                return Ok(segments);
+            };
+
+            segments.push(segment);
+        }
+    }
+
+    /// Parses `[selector, ...]`.
+    fn parse_bracketed(&mut self) -> Result<Vec<Selector>> {
+        self.expect('[', "'['")?;
+        let mut selectors = Vec::new();
+
+        loop {
+            self.skip_blank();
+            selectors.push(self.parse_selector()?);
+            self.skip_blank();
+
+            if self.eat(']') {
+                return Ok(selectors);
+            }
+
+            self.expect(',', "',' or ']'")?;
+        }
+    }
+
+    fn parse_selector(&mut self) -> Result<Selector> {
+        match self.peek() {
+            Some('\'' | '"') => Ok(Selector::Name(self.parse_string()?)),
+            Some('*') => {
+                self.bump();
+                Ok(Selector::Wildcard)
+            }
+            Some('?') => {
+                self.bump();
+                self.skip_blank();
+                Ok(Selector::Filter(self.parse_or()?))
+            }
+            Some(':') => self.parse_slice(None),
+            Some('-' | '0'..='9') => {
+                let start = self.parse_integer()?;
+                let before_colon = self.pos;
+                self.skip_blank();
+
+                if self.peek() == Some(':') {
+                    self.parse_slice(Some(start))
+                } else {
+                    self.pos = before_colon;
+                    Ok(Selector::Index(start))
+                }
+            }
+            _ => Err(self.unexpected("a selector")),
+        }
+    }
+
+    /// Parses the rest of `start:end:step` after `start`.
+    fn parse_slice(&mut self, start: Option<i64>) -> Result<Selector> {
+        self.expect(':', "':'")?;
+        self.skip_blank();
+        let end = self.parse_optional_integer()?;
+        self.skip_blank();
+
+        let step = if self.eat(':') {
+            self.skip_blank();
+            self.parse_optional_integer()?
+        } else {
+            None
+        };
+
+        Ok(Selector::Slice { start, end, step })
+    }
+
+    fn parse_optional_integer(&mut self) -> Result<Option<i64>> {
+        match self.peek() {
+            Some('-' | '0'..='9') => self.parse_integer().map(Some),
+            _ => Ok(None),
+        }
+    }
+
+    fn parse_integer(&mut self) -> Result<i64> {
+        let start = self.pos;
+        self.eat('-');
+        self.take_while(|c| c.is_ascii_digit());
+
+        let text = &self.input[start..self.pos];
+        let digits = text.trim_start_matches('-');
+        let valid =
+            digits == "0" && text == "0" || digits.starts_with(|c: char| ('1'..='9').contains(&c));
+
+        match text.parse::<i64>() {
+            Ok(n) if valid && n.abs() <= MAX_INTEGER => Ok(n),
+            _ => Err(self.error_at(start, PathErrorKind::InvalidInteger)),
+        }
+    }
+
+    /// Parses the name after `.` or `..`, which may not be quoted.
+    fn parse_member_name(&mut self) -> Result<String> {
+        match self.peek() {
+            Some(c) if c == '_' || c.is_ascii_alphabetic() || !c.is_ascii() => {
+                let name =
+                    self.take_while(|c| c == '_' || c.is_ascii_alphanumeric() || !c.is_ascii());
+                Ok(name.to_string())
+            }
+            _ => Err(self.unexpected("a member name or '*'")),
+        }
+    }
+
+    /// Parses a string literal in single or double quotes.
+    fn parse_string(&mut self) -> Result<String> {
+        let start = self.pos;
+        let quote = self.bump().expect("caller checked for a quote");
+        let mut s = String::new();
+
+        loop {
+            match self.bump() {
+                None => return Err(self.error_at(start, PathErrorKind::UnterminatedString)),
+                Some(c) if c == quote => return Ok(s),
+                Some('\\') => {
+                    let escape_start = self.pos - 1;
+                    let c = match self.bump() {
+                        Some('b') => '\u{8}',
+                        Some('f') => '\u{c}',
+                        Some('n') => '\n',
+                        Some('r') => '\r',
+                        Some('t') => '\t',
+                        Some('/') => '/',
+                        Some('\\') => '\\',
+                        Some(c) if c == quote => c,
+                        Some('u') => self.parse_unicode_escape().ok_or_else(|| {
+                            self.error_at(escape_start, PathErrorKind::InvalidEscape)
+                        })?,
+                        _ => return Err(self.error_at(escape_start, PathErrorKind::InvalidEscape)),
+                    };
+
+                    s.push(c);
+                }
+                Some(c) if c < ' ' => {
+                    return Err(self.unexpected_at(self.pos - 1, "a printable character"));
+                }
+                Some(c) => s.push(c),
+            }
+        }
+    }
+
+    /// Parses the digits of a `\u` escape, including the low half of a
+    /// surrogate pair.
+    fn parse_unicode_escape(&mut self) -> Option<char> {
+        let high = self.parse_hex()?;
+
+        if !(0xD800..0xDC00).contains(&high) {
+            return char::from_u32(high);
+        }
+
+        if !self.eat_str("\\u") {
+            return None;
+        }
+
+        let low = self.parse_hex()?;
+
+        if !(0xDC00..0xE000).contains(&low) {
+            return None;
+        }
+
+        char::from_u32(0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00))
+    }
+
+    fn parse_hex(&mut self) -> Option<u32> {
+        let hex = self.input.get(self.pos..self.pos + 4)?;
+
+        if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+
+        self.pos += 4;
+        u32::from_str_radix(hex, 16).ok()
+    }
+
+    fn parse_or(&mut self) -> Result<Expr> {
+        let mut operands = vec![self.parse_and()?];
+
+        while self.eat_operator("||") {
+            operands.push(self.parse_and()?);
+        }
+
+        Ok(if operands.len() == 1 {
+            operands.pop().expect("one operand")
+        } else {
+            Expr::Or(operands)
+        })
+    }
+
+    fn parse_and(&mut self) -> Result<Expr> {
+        let mut operands = vec![self.parse_basic()?];
+
+        while self.eat_operator("&&") {
+            operands.push(self.parse_basic()?);
+        }
+
+        Ok(if operands.len() == 1 {
+            operands.pop().expect("one operand")
+        } else {
+            Expr::And(operands)
+        })
+    }
+
+    /// Parses a parenthesized expression, an existence test or a comparison,
+    /// optionally negated with `!`.
+    fn parse_basic(&mut self) -> Result<Expr> {
+        if self.eat('!') {
+            self.skip_blank();
+
+            return if self.peek() == Some('(') {
+                Ok(Expr::Not(Box::new(self.parse_paren()?)))
+            } else {
+                let query = self.parse_filter_query()?;
+                Ok(Expr::Not(Box::new(Expr::Exists(query))))
+            };
+        }
+
+        if self.peek() == Some('(') {
+            return self.parse_paren();
+        }
+
+        let start = self.pos;
+        let left = self.parse_comparable()?;
+
+        let Some(op) = self.parse_op() else {
+            return match left {
+                Comparable::Query(query) => Ok(Expr::Exists(query)),
+                Comparable::Literal(_) => Err(self.unexpected("a comparison operator")),
+            };
+        };
+
+        self.skip_blank();
+        let right_start = self.pos;
+        let right = self.parse_comparable()?;
+
+        for (comparable, start) in [(&left, start), (&right, right_start)] {
+            if let Comparable::Query(query) = comparable
+                && !query.is_singular()
+            {
+                return Err(self.error_at(start, PathErrorKind::NonSingularQuery));
+            }
+        }
+
+        Ok(Expr::Compare(left, op, right))
+    }
+
+    fn parse_paren(&mut self) -> Result<Expr> {
+        self.expect('(', "'('")?;
+        self.skip_blank();
+        let expr = self.parse_or()?;
+        self.skip_blank();
+        self.expect(')', "')'")?;
+
+        Ok(expr)
+    }
+
+    fn parse_op(&mut self) -> Option<Op> {
+        let start = self.pos;
+        self.skip_blank();
+
+        for (text, op) in [
+            ("==", Op::Eq),
+            ("!=", Op::Ne),
+            ("<=", Op::Le),
+            (">=", Op::Ge),
+            ("<", Op::Lt),
+            (">", Op::Gt),
+        ] {
+            if self.eat_str(text) {
+                return Some(op);
+            }
+        }
+
+        self.pos = start;
+        None
+    }
+
+    fn parse_comparable(&mut self) -> Result<Comparable> {
+        match self.peek() {
+            Some('@' | '$') => Ok(Comparable::Query(self.parse_filter_query()?)),
+            Some('\'' | '"') => Ok(Comparable::Literal(Value::String(Cow::Owned(
+                self.parse_string()?,
+            )))),
+            Some('-' | '0'..='9') => {
+                let start = self.pos;
+                let text = self.take_while(|c| c.is_ascii_digit() || "+-.eE".contains(c));
+
+                match Number::parse(text) {
+                    Some(number) => Ok(Comparable::Literal(Value::Number(number.into_owned()))),
+                    None => {
+                        Err(self.error_at(start, PathErrorKind::InvalidNumber(text.to_string())))
+                    }
+                }
+            }
+            Some(c) if c.is_ascii_lowercase() => {
+                let start = self.pos;
+                let word = self.take_while(|c| c == '_' || c.is_ascii_alphanumeric());
+
+                match word {
+                    "true" => Ok(Comparable::Literal(Value::Boolean(true))),
+                    "false" => Ok(Comparable::Literal(Value::Boolean(false))),
+                    "null" => Ok(Comparable::Literal(Value::Null)),
+                    _ if self.peek() == Some('(') => {
+                        Err(self
+                            .error_at(start, PathErrorKind::UnsupportedFunction(word.to_string())))
+                    }
+                    _ => Err(self.unexpected_at(start, "a query or a literal")),
+                }
+            }
+            _ => Err(self.unexpected("a query or a literal")),
+        }
+    }
+
+    /// Parses a query starting with `@` for the current node or `$` for the
+    /// root.
+    fn parse_filter_query(&mut self) -> Result<Query> {
+        let relative = match self.peek() {
+            Some('@') => true,
+            Some('$') => false,
+            _ => return Err(self.unexpected("'@' or '$'")),
+        };
+
+        self.bump();
+        let segments = self.parse_segments()?;
+
+        Ok(Query { relative, segments })
+    }
+
+    fn eat_operator(&mut self, operator: &str) -> bool {
+        let start = self.pos;
+        self.skip_blank();
+
+        if self.eat_str(operator) {
+            self.skip_blank();
+            true
+        } else {
+            self.pos = start;
+            false
+        }
+    }
+
+    fn expect(&mut self, c: char, expected: &'static str) -> Result<()> {
+        if self.eat(c) {
+            Ok(())
+        } else {
+            Err(self.unexpected(expected))
+        }
+    }
+
+    fn skip_blank(&mut self) {
+        self.take_while(|c| matches!(c, ' ' | '\t' | '\n' | '\r'));
+    }
+
+    fn take_while(&mut self, f: impl Fn(char) -> bool) -> &'q str {
+        let start = self.pos;
+
+        while let Some(c) = self.peek()
+            && f(c)
+        {
+            self.pos += c.len_utf8();
+        }
+
+        &self.input[start..self.pos]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.input[self.pos..].chars().next()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+
+        Some(c)
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.pos += c.len_utf8();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn eat_str(&mut self, s: &str) -> bool {
+        if self.input[self.pos..].starts_with(s) {
+            self.pos += s.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn unexpected(&self, expected: &'static str) -> PathError {
+        self.unexpected_at(self.pos, expected)
+    }
+
+    fn unexpected_at(&self, pos: usize, expected: &'static str) -> PathError {
+        let found = match self.input[pos..].chars().next() {
+            Some(c) => format!("{c:?}"),
+            None => "end of query".to_string(),
+        };
+
+        self.error_at(pos, PathErrorKind::Unexpected { expected, found })
+    }
+
+    fn error(&self, kind: PathErrorKind) -> PathError {
+        self.error_at(self.pos, kind)
+    }
+
+    fn error_at(&self, pos: usize, kind: PathErrorKind) -> PathError {
+        PathError {
+            kind,
+            col: self.input[..pos].chars().count() + 1,
+        }
+    }
+}
@@ -0,0 +1,197 @@
+use super::*;
+use crate::parser::{DEEP, parse, parse_deep};
+
+/// Returns the normalized paths of the nodes selected by `path`.
+fn paths(value: &Value<'_>, path: &str) -> Vec<String> {
+    value
+        .query(path)
+        .unwrap_or_else(|e| panic!("Failed to parse {path:?}: {e}"))
+        .iter()
+        .map(|node| node.path.to_string())
+        .collect()
+}
+
+/// The example document from RFC 9535 section 1.5.
+const BOOKSTORE: &str = r#"{ "store": {
+    "book": [
+      { "category": "reference",
+        "author": "Nigel Rees",
+        "title": "Sayings of the Century",
+        "price": 8.95
+      },
+      { "category": "fiction",
+        "author": "Evelyn Waugh",
+        "title": "Sword of Honour",
+        "price": 12.99
+      },
+      { "category": "fiction",
+        "author": "Herman Melville",
+        "title": "Moby Dick",
+        "isbn": "0-553-21311-3",
+        "price": 8.99
+      },
+      { "category": "fiction",
+        "author": "J. R. R. Tolkien",
+        "title": "The Lord of the Rings",
+        "isbn": "0-395-19395-8",
+        "price": 22.99
+      }
+    ],
+    "bicycle": {
+      "color": "red",
+      "price": 399
+    }
+  }
+}"#;
+
+#[test]
+fn bookstore_examples() {
+    let value = parse(BOOKSTORE);
+    let authors: Vec<_> = (0..4)
+        .map(|i| format!("$['store']['book'][{i}]['author']"))
+        .collect();
+
+    assert_eq!(paths(&value, "$.store.book[*].author"), authors);
+    assert_eq!(paths(&value, "$..author"), authors);
+    assert_eq!(
+        paths(&value, "$.store.*"),
+        ["$['store']['book']", "$['store']['bicycle']"]
+    );
+    assert_eq!(
+        paths(&value, "$.store..price"),
+        [
+            "$['store']['book'][0]['price']",
+            "$['store']['book'][1]['price']",
+            "$['store']['book'][2]['price']",
+            "$['store']['book'][3]['price']",
+            "$['store']['bicycle']['price']",
+        ]
+    );
+    assert_eq!(paths(&value, "$..book[2]"), ["$['store']['book'][2]"]);
+    assert_eq!(paths(&value, "$..book[-1]"), ["$['store']['book'][3]"]);
+    assert_eq!(
+        paths(&value, "$..book[0,1]"),
+        ["$['store']['book'][0]", "$['store']['book'][1]"]
+    );
+    assert_eq!(
+        paths(&value, "$..book[:2]"),
+        ["$['store']['book'][0]", "$['store']['book'][1]"]
+    );
+    assert_eq!(
+        paths(&value, "$..book[?@.isbn]"),
+        ["$['store']['book'][2]", "$['store']['book'][3]"]
+    );
+    assert_eq!(
+        paths(&value, "$..book[?@.price<10]"),
+        ["$['store']['book'][0]", "$['store']['book'][2]"]
+    );
+    assert_eq!(value.query("$..*").unwrap().len(), 27);
+
+    let titles: Vec<_> = value
+        .query("$.store.book[?@.category == 'fiction' && @.price >= 12].title")
+        .unwrap()
+        .into_iter()
+        .filter_map(|node| node.value.as_str())
+        .collect();
+    assert_eq!(titles, ["Sword of Honour", "The Lord of the Rings"]);
+}
+
+#[test]
+fn slices() {
+    let value = parse(r#"["a", "b", "c", "d", "e", "f", "g"]"#);
+    let selected = |path| -> Vec<String> {
+        value
+            .query(path)
+            .unwrap()
+            .iter()
+            .map(|node| node.value.as_str().unwrap().to_string())
+            .collect()
+    };
+
+    assert_eq!(selected("$[1:3]"), ["b", "c"]);
+    assert_eq!(selected("$[5:]"), ["f", "g"]);
+    assert_eq!(selected("$[1:5:2]"), ["b", "d"]);
+    assert_eq!(selected("$[5:1:-2]"), ["f", "d"]);
+    assert_eq!(selected("$[::-1]"), ["g", "f", "e", "d", "c", "b", "a"]);
+    assert_eq!(selected("$[-2:]"), ["f", "g"]);
+    assert!(selected("$[::0]").is_empty());
+    assert!(selected("$[10:20]").is_empty());
+}
+
+#[test]
+fn filter_comparisons() {
+    let value =
+        parse(r#"[{"a": 1}, {"a": 1.0}, {"a": "1"}, {"a": null}, {"a": [1]}, {"b": 2}, 3, "x"]"#);
+
+    assert_eq!(paths(&value, "$[?@.a == 1]"), ["$[0]", "$[1]"]);
+    assert_eq!(paths(&value, "$[?@.a == '1']"), ["$[2]"]);
+    assert_eq!(paths(&value, "$[?@.a == null]"), ["$[3]"]);
+    assert_eq!(paths(&value, "$[?@.a == $[4].a]"), ["$[4]"]);
+    assert_eq!(paths(&value, "$[?@.a == @.c]"), ["$[5]", "$[6]", "$[7]"]);
+    assert_eq!(paths(&value, "$[?@.a < 2]"), ["$[0]", "$[1]"]);
+    assert_eq!(paths(&value, "$[?@.a >= '0']"), ["$[2]"]);
+    assert_eq!(paths(&value, "$[?@ > 2]"), ["$[6]"]);
+    assert_eq!(paths(&value, "$[?!@.a]"), ["$[5]", "$[6]", "$[7]"]);
+    assert_eq!(
+        paths(&value, "$[?@.a != 1 && (@.b || @ == 'x')]"),
+        ["$[5]", "$[7]"]
+    );
+    assert_eq!(paths(&value, "$[?@.a <= null]"), ["$[3]"]);
+}
+
+#[test]
+fn names_and_normalized_paths() {
+    let value = parse(r#"{"it's": {"a\nb": [0, {"~/": true}]}}"#);
+    let nodes = value.query(r#"$["it's"]['a\nb'][1]['~/']"#).unwrap();
+
+    assert_eq!(nodes.len(), 1);
+    assert_eq!(nodes[0].path.to_string(), r"$['it\'s']['a\nb'][1]['~/']");
+    assert_eq!(nodes[0].path.to_pointer().to_string(), "/it's/a\nb/1/~0~1");
+    assert_eq!(
+        value.pointer(&nodes[0].path.to_pointer().to_string()),
+        Ok(nodes[0].value)
+    );
+}
+
+#[test]
+fn syntax_errors() {
+    let error = |path: &str| path.parse::<JsonPath>().unwrap_err();
+
+    assert_eq!(error("store").kind, PathErrorKind::MissingRoot);
+    assert_eq!(error("$.a[01]").kind, PathErrorKind::InvalidInteger);
+    assert_eq!(
+        error("$[9007199254740992]").kind,
+        PathErrorKind::InvalidInteger
+    );
+    assert_eq!(error("$['abc").kind, PathErrorKind::UnterminatedString);
+    assert_eq!(error(r"$['\q']").kind, PathErrorKind::InvalidEscape);
+    assert_eq!(error("$[?@.* == 1]").kind, PathErrorKind::NonSingularQuery);
+    assert_eq!(
+        error("$[?length(@) > 1]").kind,
+        PathErrorKind::UnsupportedFunction("length".to_string())
+    );
+    assert!(matches!(
+        error("$[?1]").kind,
+        PathErrorKind::Unexpected { .. }
+    ));
+    assert!(matches!(
+        error("$.a b").kind,
+        PathErrorKind::Unexpected { .. }
+    ));
+
+    let error = error("$.a[?@.b ==]");
+    assert_eq!(error.col, 12);
+    assert_eq!(
+        error.to_string(),
+        "Error at col 12: Expected a query or a literal, found ']'"
+    );
+}
+
+#[test]
+fn descendants_of_deep_documents() {
+    let value = parse_deep("[", "1");
+
+    let numbers = value.query("$..[?@ == 1]").unwrap();
+    assert_eq!(numbers.len(), 1);
+    assert_eq!(numbers[0].path.elements().len(), DEEP);
+}
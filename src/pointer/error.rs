@@ -0,0 +1,31 @@
+use thiserror::Error;
+
+use crate::parser::ValueKind;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum PointerError {
+    #[error("Json pointers must be empty or start with '/', found {pointer:?}")]
+    MissingSlash { pointer: String },
+
+    #[error("'~' must be followed by '0' or '1' in json pointer {pointer:?}")]
+    InvalidEscape { pointer: String },
+
+    #[error("Object at {parent:?} has no key {key:?}")]
+    MissingKey { parent: String, key: String },
+
+    #[error("Index {index:?} is out of range for the array of length {len} at {parent:?}")]
+    IndexOutOfRange {
+        parent: String,
+        index: String,
+        len: usize,
+    },
+
+    #[error(
+        "Cannot look up {token:?} in the {kind} at {parent:?}, only objects and arrays have members"
+    )]
+    NotAContainer {
+        parent: String,
+        token: String,
+        kind: ValueKind,
+    },
+}
@@ -0,0 +1,221 @@
+pub mod error;
+
+pub use error::PointerError;
+
+use std::{fmt, str::FromStr};
+
+use crate::parser::Value;
+
+/// A parsed RFC 6901 json pointer such as `/a/0/b`, which addresses a value
+/// within a document by the keys and indices leading to it.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct JsonPointer {
+    /// The unescaped reference tokens, so `/a~1b` holds `a/b`.
+    tokens: Vec<String>,
+}
+
+impl JsonPointer {
+    /// The pointer to the whole document, written as the empty string.
+    pub fn root() -> Self {
+        Self::default()
+    }
+
+    pub fn tokens(&self) -> &[String] {
+        &self.tokens
+    }
+
+    pub fn is_root(&self) -> bool {
+        self.tokens.is_empty()
+    }
+
+    /// Splits off the last token, returning the pointer to its parent, or
+    /// `None` for the root.
+    pub fn split_last(&self) -> Option<(JsonPointer, &str)> {
+        let (last, parent) = self.tokens.split_last()?;
+        let parent = JsonPointer {
+            tokens: parent.to_vec(),
+        };
+
+        Some((parent, last))
+    }
+
+    /// Returns a pointer to the member or element `token` of this one.
+    pub fn join(&self, token: impl Into<String>) -> JsonPointer {
+        let mut tokens = self.tokens.clone();
+        tokens.push(token.into());
+
+        JsonPointer { tokens }
+    }
+
+    pub fn resolve<'v, 'a>(&self, value: &'v Value<'a>) -> Result<&'v Value<'a>, PointerError> {
+        let mut current = value;
+
+        for (i, token) in self.tokens.iter().enumerate() {
+            current = match current {
+                Value::Object(object) => object.get(token).ok_or_else(|| self.missing_key(i))?,
+                Value::Array(array) => {
+                    let index = self.array_index(i, array.len())?;
+                    &array[index]
+                }
+                other => return Err(self.not_a_container(i, other)),
+            };
+        }
+
+        Ok(current)
+    }
+
+    pub fn resolve_mut<'v, 'a>(
+        &self,
+        value: &'v mut Value<'a>,
+    ) -> Result<&'v mut Value<'a>, PointerError> {
+        let mut current = value;
+
+        for (i, token) in self.tokens.iter().enumerate() {
+            current = match current {
+                Value::Object(object) => match object.get_mut(token) {
+                    Some(member) => member,
+                    None => return Err(self.missing_key(i)),
+                },
+                Value::Array(array) => {
+                    let index = self.array_index(i, array.len())?;
+                    &mut array[index]
+                }
+                other => return Err(self.not_a_container(i, other)),
+            };
+        }
+
+        Ok(current)
+    }
+
+    /// Parses the token at `i` as an index into an array of length `len`.
+    ///
+    /// RFC 6901 only allows digits without leading zeros, and `-` for the
+    /// nonexistent element after the last, which is always out of range here.
+    pub(crate) fn array_index(&self, i: usize, len: usize) -> Result<usize, PointerError> {
+        let token = &self.tokens[i];
+
+        parse_index(token)
+            .filter(|&index| index < len)
+            .ok_or_else(|| PointerError::IndexOutOfRange {
+                parent: self.prefix(i),
+                index: token.clone(),
+                len,
+            })
+    }
+
+    pub(crate) fn missing_key(&self, i: usize) -> PointerError {
+        PointerError::MissingKey {
+            parent: self.prefix(i),
+            key: self.tokens[i].clone(),
+        }
+    }
+
+    pub(crate) fn not_a_container(&self, i: usize, value: &Value<'_>) -> PointerError {
+        PointerError::NotAContainer {
+            parent: self.prefix(i),
+            token: self.tokens[i].clone(),
+            kind: value.kind(),
+        }
+    }
+
+    /// Formats the first `len` tokens, which address the parent of token
+    /// `len`.
+    fn prefix(&self, len: usize) -> String {
+        JsonPointer {
+            tokens: self.tokens[..len].to_vec(),
+        }
+        .to_string()
+    }
+}
+
+/// Parses an RFC 6901 array index, which has no sign or leading zeros.
+pub(crate) fn parse_index(token: &str) -> Option<usize> {
+    let valid = match token.as_bytes() {
+        [b'0'] => true,
+        [b'1'..=b'9', rest @ ..] => rest.iter().all(u8::is_ascii_digit),
+        _ => false,
+    };
+
+    if valid { token.parse().ok() } else { None }
+}
+
+/// Builds a pointer from unescaped reference tokens.
+impl FromIterator<String> for JsonPointer {
+    fn from_iter<I: IntoIterator<Item = String>>(tokens: I) -> Self {
+        JsonPointer {
+            tokens: tokens.into_iter().collect(),
+        }
+    }
+}
+
+impl FromStr for JsonPointer {
+    type Err = PointerError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Ok(Self::root());
+        }
+
+        let Some(rest) = s.strip_prefix('/') else {
+            return Err(PointerError::MissingSlash {
+                pointer: s.to_string(),
+            });
+        };
+
+        let tokens = rest
+            .split('/')
+            .map(|token| {
+                unescape(token).ok_or_else(|| PointerError::InvalidEscape {
+                    pointer: s.to_string(),
+                })
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self { tokens })
+    }
+}
+
+/// Decodes `~1` to `/` and `~0` to `~`, failing on any other use of `~`.
+fn unescape(token: &str) -> Option<String> {
+    let mut unescaped = String::with_capacity(token.len());
+    let mut chars = token.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '~' => match chars.next()? {
+                '0' => unescaped.push('~'),
+                '1' => unescaped.push('/'),
+                _ => return None,
+            },
+            c => unescaped.push(c),
+        }
+    }
+
+    Some(unescaped)
+}
+
+impl fmt::Display for JsonPointer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for token in &self.tokens {
+            write!(f, "/{}", token.replace('~', "~0").replace('/', "~1"))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Value<'a> {
+    /// Looks up the value addressed by an RFC 6901 json pointer such as
+    /// `/a/0/b`, where `~1` stands for `/` and `~0` for `~` within a key.
+    pub fn pointer(&self, pointer: &str) -> Result<&Value<'a>, PointerError> {
+        pointer.parse::<JsonPointer>()?.resolve(self)
+    }
+
+    /// Like [`Value::pointer`], but returns a mutable reference.
+    pub fn pointer_mut(&mut self, pointer: &str) -> Result<&mut Value<'a>, PointerError> {
+        pointer.parse::<JsonPointer>()?.resolve_mut(self)
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,116 @@
+use super::*;
+use crate::parser::{ValueKind, parse};
+
+/// The example document from RFC 6901 section 5.
+const RFC_EXAMPLE: &str = r#"{
+    "foo": ["bar", "baz"],
+    "": 0,
+    "a/b": 1,
+    "c%d": 2,
+    "e^f": 3,
+    "g|h": 4,
+    "i\\j": 5,
+    "k\"l": 6,
+    " ": 7,
+    "m~n": 8
+}"#;
+
+#[test]
+fn rfc_examples() {
+    let value = parse(RFC_EXAMPLE);
+
+    assert_eq!(value.pointer(""), Ok(&value));
+    assert_eq!(value.pointer("/foo"), Ok(&value["foo"]));
+    assert_eq!(value.pointer("/foo/0").unwrap().as_str(), Some("bar"));
+
+    let expected = [
+        ("/", 0),
+        ("/a~1b", 1),
+        ("/c%d", 2),
+        ("/e^f", 3),
+        ("/g|h", 4),
+        ("/i\\j", 5),
+        ("/k\"l", 6),
+        ("/ ", 7),
+        ("/m~0n", 8),
+    ];
+
+    for (pointer, n) in expected {
+        let number = value.pointer(pointer).unwrap().as_number().unwrap();
+        assert_eq!(number.as_u64(), Ok(n), "pointer: {pointer}");
+    }
+}
+
+#[test]
+fn parse_and_display() {
+    let pointer: JsonPointer = "/a~1b/~0/0/".parse().unwrap();
+
+    assert_eq!(pointer.tokens(), ["a/b", "~", "0", ""]);
+    assert_eq!(pointer.to_string(), "/a~1b/~0/0/");
+    assert!("".parse::<JsonPointer>().unwrap().is_root());
+
+    assert!(matches!(
+        "a/b".parse::<JsonPointer>(),
+        Err(PointerError::MissingSlash { .. })
+    ));
+    assert!(matches!(
+        "/a~2".parse::<JsonPointer>(),
+        Err(PointerError::InvalidEscape { .. })
+    ));
+    assert!(matches!(
+        "/a~".parse::<JsonPointer>(),
+        Err(PointerError::InvalidEscape { .. })
+    ));
+}
+
+#[test]
+fn lookup_errors() {
+    let value = parse(r#"{"a": {"b": [1, 2]}}"#);
+
+    assert_eq!(
+        value.pointer("/a/c"),
+        Err(PointerError::MissingKey {
+            parent: "/a".to_string(),
+            key: "c".to_string(),
+        })
+    );
+    assert_eq!(
+        value.pointer("/a/b/2"),
+        Err(PointerError::IndexOutOfRange {
+            parent: "/a/b".to_string(),
+            index: "2".to_string(),
+            len: 2,
+        })
+    );
+    assert_eq!(
+        value.pointer("/a/b/0/x"),
+        Err(PointerError::NotAContainer {
+            parent: "/a/b/0".to_string(),
+            token: "x".to_string(),
+            kind: ValueKind::Number,
+        })
+    );
+
+    for index in ["-", "01", "+1", "x", ""] {
+        assert!(
+            matches!(
+                value.pointer(&format!("/a/b/{index}")),
+                Err(PointerError::IndexOutOfRange { .. })
+            ),
+            "index: {index}"
+        );
+    }
+}
+
+#[test]
+fn pointer_mut() {
+    let mut value = parse(r#"{"a": [{"b": 1}]}"#);
+
+    *value.pointer_mut("/a/0/b").unwrap() = Value::Boolean(true);
+    assert_eq!(value, parse(r#"{"a": [{"b": true}]}"#));
+
+    assert!(matches!(
+        value.pointer_mut("/a/1"),
+        Err(PointerError::IndexOutOfRange { len: 1, .. })
+    ));
+}
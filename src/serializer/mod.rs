@@ -0,0 +1,440 @@
+#[cfg(feature = "serde")]
+pub(crate) mod ser;
+
+#[cfg(feature = "serde")]
+pub use ser::SerializeError;
+
+use std::{fmt, io, slice, vec};
+
+use crate::parser::{Value, object};
+
+/// Columns a tab is assumed to take up when deciding whether a container
+/// fits on one line.
+const TAB_WIDTH: usize = 4;
+
+/// Indentation for each level of nesting in pretty output.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Indent {
+    Spaces(usize),
+    Tabs(usize),
+}
+
+impl Indent {
+    fn width(self) -> usize {
+        match self {
+            Indent::Spaces(n) => n,
+            Indent::Tabs(n) => n * TAB_WIDTH,
+        }
+    }
+
+    fn write<W: io::Write>(self, writer: &mut W, depth: usize) -> io::Result<()> {
+        let (byte, n) = match self {
+            Indent::Spaces(n) => (b' ', n),
+            Indent::Tabs(n) => (b'\t', n),
+        };
+
+        writer.write_all(&vec![byte; n * depth])
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct SerializerOptions {
+    /// Write every non-ASCII character as a `\uXXXX` escape, so that the
+    /// output is plain ASCII.
+    pub escape_non_ascii: bool,
+    /// Write object members ordered by key instead of insertion order.
+    pub sort_keys: bool,
+    /// Put each member on its own line with this indentation, or write
+    /// compact output without any insignificant whitespace when `None`.
+    pub indent: Option<Indent>,
+    /// In indented output, keep an array or object on one line if it fits
+    /// within this many columns. `None` always spreads containers out.
+    pub max_width: Option<usize>,
+    /// End the output with a newline.
+    pub trailing_newline: bool,
+}
+
+impl SerializerOptions {
+    /// Options for pretty output, indented by two spaces with short
+    /// containers kept on one line.
+    pub fn pretty() -> Self {
+        Self {
+            indent: Some(Indent::Spaces(2)),
+            max_width: Some(80),
+            ..Default::default()
+        }
+    }
+}
+
+/// Writes a [`Value`] as json, either compact or pretty printed depending on
+/// its [`SerializerOptions`].
+pub struct Serializer<W> {
+    writer: W,
+    options: SerializerOptions,
+}
+
+impl<W: io::Write> Serializer<W> {
+    pub fn new(writer: W) -> Self {
+        Self::with_options(writer, SerializerOptions::default())
+    }
+
+    pub fn pretty(writer: W) -> Self {
+        Self::with_options(writer, SerializerOptions::pretty())
+    }
+
+    pub fn with_options(writer: W, options: SerializerOptions) -> Self {
+        Self { writer, options }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    pub fn serialize(&mut self, value: &Value<'_>) -> io::Result<()> {
+        match self.options.indent {
+            None => write_flat(&mut self.writer, value, &self.options, b",", b":")?,
+            Some(indent) => write_pretty(&mut self.writer, value, &self.options, indent)?,
+        }
+
+        if self.options.trailing_newline {
+            self.writer.write_all(b"\n")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Writes `value` as compact json to `writer`.
+pub fn to_writer<W: io::Write>(writer: W, value: &Value<'_>) -> io::Result<()> {
+    Serializer::new(writer).serialize(value)
+}
+
+/// Writes `value` as pretty printed json to `writer`.
+pub fn to_writer_pretty<W: io::Write>(writer: W, value: &Value<'_>) -> io::Result<()> {
+    Serializer::pretty(writer).serialize(value)
+}
+
+/// Returns `value` as a compact json string.
+pub fn to_string(value: &Value<'_>) -> String {
+    to_string_with_options(value, SerializerOptions::default())
+}
+
+/// Returns `value` as a pretty printed json string.
+pub fn to_string_pretty(value: &Value<'_>) -> String {
+    to_string_with_options(value, SerializerOptions::pretty())
+}
+
+pub fn to_string_with_options(value: &Value<'_>, options: SerializerOptions) -> String {
+    let mut serializer = Serializer::with_options(Vec::new(), options);
+    serializer
+        .serialize(value)
+        .expect("writing to a Vec cannot fail");
+
+    String::from_utf8(serializer.into_inner()).expect("serialized json is valid UTF-8")
+}
+
+/// Formats the value as compact json, or pretty printed with `{:#}`.
+impl fmt::Display for Value<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = if f.alternate() {
+            to_string_pretty(self)
+        } else {
+            to_string(self)
+        };
+
+        f.write_str(&s)
+    }
+}
+
+/// The members of a container that are still to be written, with object
+/// members paired with their key.
+enum Members<'b, 'a> {
+    Array(slice::Iter<'b, Value<'a>>),
+    Object(object::Iter<'b, 'a>),
+    Sorted(vec::IntoIter<(&'b str, &'b Value<'a>)>),
+}
+
+impl<'b, 'a> Members<'b, 'a> {
+    /// Returns `None` for values that are not containers.
+    fn of(value: &'b Value<'a>, sort_keys: bool) -> Option<Self> {
+        match value {
+            Value::Array(array) => Some(Members::Array(array.iter())),
+            Value::Object(object) if sort_keys => {
+                let mut members: Vec<_> = object.iter().collect();
+                members.sort_by_key(|(key, _)| *key);
+
+                Some(Members::Sorted(members.into_iter()))
+            }
+            Value::Object(object) => Some(Members::Object(object.iter())),
+            _ => None,
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            Members::Array(iter) => iter.len(),
+            Members::Object(iter) => iter.len(),
+            Members::Sorted(iter) => iter.len(),
+        }
+    }
+
+    fn brackets(&self) -> (&'static [u8], &'static [u8]) {
+        match self {
+            Members::Array(_) => (b"[", b"]"),
+            Members::Object(_) | Members::Sorted(_) => (b"{", b"}"),
+        }
+    }
+}
+
+impl<'b, 'a> Iterator for Members<'b, 'a> {
+    type Item = (Option<&'b str>, &'b Value<'a>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Members::Array(iter) => iter.next().map(|value| (None, value)),
+            Members::Object(iter) => iter.next().map(|(key, value)| (Some(key), value)),
+            Members::Sorted(iter) => iter.next().map(|(key, value)| (Some(key), value)),
+        }
+    }
+}
+
+/// A container whose members are still being written.
+struct Frame<'b, 'a> {
+    members: Members<'b, 'a>,
+    close: &'static [u8],
+    first: bool,
+}
+
+/// Writes `value` on a single line, with `item_separator` between members
+/// and `key_separator` between keys and values.
+fn write_flat<W: io::Write>(
+    writer: &mut W,
+    value: &Value<'_>,
+    options: &SerializerOptions,
+    item_separator: &[u8],
+    key_separator: &[u8],
+) -> io::Result<()> {
+    let mut stack: Vec<Frame> = Vec::new();
+    let mut next = Some(value);
+
+    loop {
+        if let Some(value) = next.take() {
+            match Members::of(value, options.sort_keys) {
+                Some(members) => {
+                    let (open, close) = members.brackets();
+                    writer.write_all(open)?;
+                    stack.push(Frame {
+                        members,
+                        close,
+                        first: true,
+                    });
+                }
+                None => write_scalar(writer, value, options)?,
+            }
+        }
+
+        let Some(frame) = stack.last_mut() else {
+            return Ok(());
+        };
+
+        match frame.members.next() {
+            Some((key, value)) => {
+                if !frame.first {
+                    writer.write_all(item_separator)?;
+                }
+
+                frame.first = false;
+
+                if let Some(key) = key {
+                    write_string(writer, key, options)?;
+                    writer.write_all(key_separator)?;
+                }
+
+                next = Some(value);
+            }
+            None => {
+                writer.write_all(frame.close)?;
+                stack.pop();
+            }
+        }
+    }
+}
+
+/// Writes `value` with each member on its own line, keeping containers that
+/// fit within `max_width` on one line.
+fn write_pretty<W: io::Write>(
+    writer: &mut W,
+    value: &Value<'_>,
+    options: &SerializerOptions,
+    indent: Indent,
+) -> io::Result<()> {
+    let mut stack: Vec<Frame> = Vec::new();
+    // The next value to write, the column it starts at, and whether a comma
+    // will follow it.
+    let mut next = Some((value, 0, false));
+
+    loop {
+        if let Some((value, column, comma)) = next.take() {
+            match Members::of(value, options.sort_keys) {
+                Some(members) => {
+                    let (open, close) = members.brackets();
+                    let fits = members.len() == 0
+                        || options.max_width.is_some_and(|max_width| {
+                            let budget = max_width.saturating_sub(column + usize::from(comma));
+                            fits_within(value, options, budget)
+                        });
+
+                    if fits {
+                        write_flat(writer, value, options, b", ", b": ")?;
+                    } else {
+                        writer.write_all(open)?;
+                        stack.push(Frame {
+                            members,
+                            close,
+                            first: true,
+                        });
+                    }
+                }
+                None => write_scalar(writer, value, options)?,
+            }
+        }
+
+        let depth = stack.len();
+        let Some(frame) = stack.last_mut() else {
+            return Ok(());
+        };
+
+        match frame.members.next() {
+            Some((key, value)) => {
+                if !frame.first {
+                    writer.write_all(b",")?;
+                }
+
+                frame.first = false;
+                writer.write_all(b"\n")?;
+                indent.write(writer, depth)?;
+
+                let mut column = indent.width() * depth;
+
+                if let Some(key) = key {
+                    column += write_key(writer, key, options)?;
+                }
+
+                next = Some((value, column, frame.members.len() > 0));
+            }
+            None => {
+                writer.write_all(b"\n")?;
+                indent.write(writer, depth - 1)?;
+                writer.write_all(frame.close)?;
+                stack.pop();
+            }
+        }
+    }
+}
+
+/// Writes an object key and the separator after it in pretty output,
+/// returning how many columns they take up.
+fn write_key<W: io::Write>(
+    writer: &mut W,
+    key: &str,
+    options: &SerializerOptions,
+) -> io::Result<usize> {
+    let mut buf = Vec::new();
+    write_string(&mut buf, key, options)?;
+    writer.write_all(&buf)?;
+    writer.write_all(b": ")?;
+
+    Ok(String::from_utf8_lossy(&buf).chars().count() + 2)
+}
+
+/// Whether the single-line form of `value` is at most `budget` characters
+/// long.
+fn fits_within(value: &Value<'_>, options: &SerializerOptions, budget: usize) -> bool {
+    write_flat(&mut Limited(budget), value, options, b", ", b": ").is_ok()
+}
+
+/// A writer that discards its input and fails once more characters were
+/// written to it than it started with.
+struct Limited(usize);
+
+impl io::Write for Limited {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        // Count the first byte of each UTF-8 sequence, which works even when
+        // a character is split across writes.
+        let chars = buf.iter().filter(|&&b| !is_continuation(b)).count();
+        self.0 = self.0.checked_sub(chars).ok_or(io::ErrorKind::WriteZero)?;
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fn is_continuation(b: u8) -> bool {
+    b & 0xC0 == 0x80
+}
+
+/// Writes any value other than an object or array.
+pub(crate) fn write_scalar<W: io::Write>(
+    writer: &mut W,
+    value: &Value<'_>,
+    options: &SerializerOptions,
+) -> io::Result<()> {
+    match value {
+        Value::String(s) => write_string(writer, s, options),
+        Value::Number(n) => writer.write_all(n.as_str().as_bytes()),
+        Value::Boolean(true) => writer.write_all(b"true"),
+        Value::Boolean(false) => writer.write_all(b"false"),
+        Value::Null => writer.write_all(b"null"),
+        Value::Array(_) | Value::Object(_) => unreachable!("containers are not scalars"),
+    }
+}
+
+/// Writes `s` as a quoted json string, escaping quotes, backslashes and
+/// control characters.
+pub(crate) fn write_string<W: io::Write>(
+    writer: &mut W,
+    s: &str,
+    options: &SerializerOptions,
+) -> io::Result<()> {
+    writer.write_all(b"\"")?;
+
+    // Runs of characters that need no escaping are copied in one write.
+    let mut start = 0;
+
+    for (i, c) in s.char_indices() {
+        let escape = match c {
+            '"' => Some("\\\""),
+            '\\' => Some("\\\\"),
+            '\u{08}' => Some("\\b"),
+            '\u{0C}' => Some("\\f"),
+            '\n' => Some("\\n"),
+            '\r' => Some("\\r"),
+            '\t' => Some("\\t"),
+            c if c < ' ' || (options.escape_non_ascii && !c.is_ascii()) => None,
+            _ => continue,
+        };
+
+        writer.write_all(&s.as_bytes()[start..i])?;
+        start = i + c.len_utf8();
+
+        match escape {
+            Some(escape) => writer.write_all(escape.as_bytes())?,
+            None => {
+                let mut units = [0; 2];
+
+                for unit in c.encode_utf16(&mut units) {
+                    write!(writer, "\\u{unit:04x}")?;
+                }
+            }
+        }
+    }
+
+    writer.write_all(&s.as_bytes()[start..])?;
+    writer.write_all(b"\"")
+}
+
+#[cfg(test)]
+mod test;
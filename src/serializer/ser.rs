@@ -0,0 +1,1276 @@
+use std::io;
+
+use serde::ser::{self, Impossible, Serialize};
+use thiserror::Error;
+
+use super::{Indent, Limited, SerializerOptions, write_key, write_string};
+use crate::parser::number::NUMBER_TOKEN;
+use crate::parser::{Array, Number, Object, Value};
+
+#[derive(Debug, Error)]
+pub enum SerializeError {
+    #[error("{0}")]
+    Io(#[from] io::Error),
+    #[error("Object keys must be strings, numbers or booleans, found {0}")]
+    InvalidKey(&'static str),
+    #[error("json cannot represent the number {0}")]
+    NonFinite(f64),
+    #[error("{0}")]
+    Custom(String),
+}
+
+impl ser::Error for SerializeError {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        Self::Custom(msg.to_string())
+    }
+}
+
+type Result<T> = std::result::Result<T, SerializeError>;
+
+/// Converts any `Serialize` type into a [`Value`].
+///
+/// Unit variants become strings and every other enum variant becomes an
+/// object with the variant name as its only key. Floats that json cannot
+/// represent are an error.
+pub fn to_value<T: Serialize + ?Sized>(value: &T) -> Result<Value<'static>> {
+    value.serialize(ValueSerializer)
+}
+
+/// Writes `value` as json to `writer` with the given options.
+///
+/// The json is written as `value` is serialized. Only objects with
+/// `sort_keys`, and the fields of enum variants in pretty output, are
+/// collected before being written, since their layout depends on members
+/// that come later.
+pub fn to_writer_with_options<W: io::Write, T: Serialize + ?Sized>(
+    mut writer: W,
+    value: &T,
+    options: SerializerOptions,
+) -> Result<()> {
+    match options.indent {
+        None => value.serialize(JsonSerializer::new(&mut writer, &options, COMPACT))?,
+        Some(indent) => {
+            write_pretty(&mut writer, value, &options, indent, 0, 0, Some(false))?;
+        }
+    }
+
+    if options.trailing_newline {
+        writer.write_all(b"\n")?;
+    }
+
+    Ok(())
+}
+
+/// Writes `value` as compact json to `writer`.
+pub fn to_writer<W: io::Write, T: Serialize + ?Sized>(writer: W, value: &T) -> Result<()> {
+    to_writer_with_options(writer, value, SerializerOptions::default())
+}
+
+/// Writes `value` as pretty printed json to `writer`.
+pub fn to_writer_pretty<W: io::Write, T: Serialize + ?Sized>(writer: W, value: &T) -> Result<()> {
+    to_writer_with_options(writer, value, SerializerOptions::pretty())
+}
+
+/// Returns `value` as a compact json string.
+pub fn to_string<T: Serialize + ?Sized>(value: &T) -> Result<String> {
+    let mut writer = Vec::new();
+    to_writer(&mut writer, value)?;
+
+    Ok(String::from_utf8(writer).expect("serialized json is valid UTF-8"))
+}
+
+/// Returns `value` as a pretty printed json string.
+pub fn to_string_pretty<T: Serialize + ?Sized>(value: &T) -> Result<String> {
+    let mut writer = Vec::new();
+    to_writer_pretty(&mut writer, value)?;
+
+    Ok(String::from_utf8(writer).expect("serialized json is valid UTF-8"))
+}
+
+fn float(number: Option<Number<'static>>, v: f64) -> Result<Number<'static>> {
+    number.ok_or(SerializeError::NonFinite(v))
+}
+
+/// Reads the exact text of a [`Number`] that was passed to
+/// `serialize_newtype_struct` under [`NUMBER_TOKEN`].
+fn exact_number<T: Serialize + ?Sized>(value: &T) -> Result<Number<'static>> {
+    match value.serialize(ValueSerializer)? {
+        Value::String(raw) if Number::parse(&raw).is_some() => Ok(Number::from_raw(raw)),
+        _ => Err(ser::Error::custom(format!(
+            "{NUMBER_TOKEN} must be a number"
+        ))),
+    }
+}
+
+/// Wraps `value` in an object keyed by the enum variant it belongs to.
+fn tagged(variant: &'static str, value: Value<'static>) -> Value<'static> {
+    let mut object = Object::new();
+    object.insert(variant, value);
+
+    Value::Object(object)
+}
+
+struct ValueSerializer;
+
+impl ser::Serializer for ValueSerializer {
+    type Ok = Value<'static>;
+    type Error = SerializeError;
+
+    type SerializeSeq = SerializeArray;
+    type SerializeTuple = SerializeArray;
+    type SerializeTupleStruct = SerializeArray;
+    type SerializeTupleVariant = SerializeArray;
+    type SerializeMap = SerializeObject;
+    type SerializeStruct = SerializeObject;
+    type SerializeStructVariant = SerializeObject;
+
+    fn serialize_bool(self, v: bool) -> Result<Value<'static>> {
+        Ok(Value::Boolean(v))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Value<'static>> {
+        Ok(Value::Number(v.into()))
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Value<'static>> {
+        Ok(Value::Number(v.into()))
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Value<'static>> {
+        Ok(Value::Number(v.into()))
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Value<'static>> {
+        Ok(Value::Number(v.into()))
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<Value<'static>> {
+        Ok(Value::Number(v.into()))
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Value<'static>> {
+        Ok(Value::Number(v.into()))
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Value<'static>> {
+        Ok(Value::Number(v.into()))
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Value<'static>> {
+        Ok(Value::Number(v.into()))
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Value<'static>> {
+        Ok(Value::Number(v.into()))
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<Value<'static>> {
+        Ok(Value::Number(v.into()))
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Value<'static>> {
+        float(Number::from_f32(v), v.into()).map(Value::Number)
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Value<'static>> {
+        float(Number::from_f64(v), v).map(Value::Number)
+    }
+
+    fn serialize_char(self, v: char) -> Result<Value<'static>> {
+        Ok(Value::String(v.to_string().into()))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Value<'static>> {
+        Ok(Value::String(v.to_owned().into()))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Value<'static>> {
+        Ok(Value::Array(
+            v.iter().map(|&b| Value::Number(b.into())).collect(),
+        ))
+    }
+
+    fn serialize_none(self) -> Result<Value<'static>> {
+        Ok(Value::Null)
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Value<'static>> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Value<'static>> {
+        Ok(Value::Null)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Value<'static>> {
+        Ok(Value::Null)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Value<'static>> {
+        Ok(Value::String(variant.into()))
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<Value<'static>> {
+        if name == NUMBER_TOKEN {
+            return exact_number(value).map(Value::Number);
+        }
+
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Value<'static>> {
+        Ok(tagged(variant, value.serialize(self)?))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<SerializeArray> {
+        Ok(SerializeArray {
+            array: Vec::with_capacity(len.unwrap_or(0)),
+            variant: None,
+        })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<SerializeArray> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, len: usize) -> Result<SerializeArray> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SerializeArray> {
+        Ok(SerializeArray {
+            array: Vec::with_capacity(len),
+            variant: Some(variant),
+        })
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<SerializeObject> {
+        Ok(SerializeObject {
+            object: Object::new(),
+            key: None,
+            variant: None,
+        })
+    }
+
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<SerializeObject> {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<SerializeObject> {
+        Ok(SerializeObject {
+            object: Object::new(),
+            key: None,
+            variant: Some(variant),
+        })
+    }
+}
+
+struct SerializeArray {
+    array: Vec<Value<'static>>,
+    variant: Option<&'static str>,
+}
+
+impl SerializeArray {
+    fn push<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        self.array.push(value.serialize(ValueSerializer)?);
+        Ok(())
+    }
+
+    fn finish(self) -> Result<Value<'static>> {
+        let array = Value::Array(Array::from(self.array));
+
+        Ok(match self.variant {
+            Some(variant) => tagged(variant, array),
+            None => array,
+        })
+    }
+}
+
+impl ser::SerializeSeq for SerializeArray {
+    type Ok = Value<'static>;
+    type Error = SerializeError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Value<'static>> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTuple for SerializeArray {
+    type Ok = Value<'static>;
+    type Error = SerializeError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Value<'static>> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleStruct for SerializeArray {
+    type Ok = Value<'static>;
+    type Error = SerializeError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Value<'static>> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleVariant for SerializeArray {
+    type Ok = Value<'static>;
+    type Error = SerializeError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Value<'static>> {
+        self.finish()
+    }
+}
+
+struct SerializeObject {
+    object: Object<'static>,
+    key: Option<String>,
+    variant: Option<&'static str>,
+}
+
+impl SerializeObject {
+    fn insert<T: Serialize + ?Sized>(&mut self, key: String, value: &T) -> Result<()> {
+        self.object.insert(key, value.serialize(ValueSerializer)?);
+        Ok(())
+    }
+
+    fn finish(self) -> Result<Value<'static>> {
+        let object = Value::Object(self.object);
+
+        Ok(match self.variant {
+            Some(variant) => tagged(variant, object),
+            None => object,
+        })
+    }
+}
+
+impl ser::SerializeMap for SerializeObject {
+    type Ok = Value<'static>;
+    type Error = SerializeError;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<()> {
+        self.key = Some(key.serialize(KeySerializer)?);
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        let key = self
+            .key
+            .take()
+            .expect("serialize_value called before serialize_key");
+
+        self.insert(key, value)
+    }
+
+    fn end(self) -> Result<Value<'static>> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeStruct for SerializeObject {
+    type Ok = Value<'static>;
+    type Error = SerializeError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        self.insert(key.to_owned(), value)
+    }
+
+    fn end(self) -> Result<Value<'static>> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeStructVariant for SerializeObject {
+    type Ok = Value<'static>;
+    type Error = SerializeError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        self.insert(key.to_owned(), value)
+    }
+
+    fn end(self) -> Result<Value<'static>> {
+        self.finish()
+    }
+}
+
+/// Serializes map keys, writing numbers and booleans as strings since json
+/// object keys are always strings.
+struct KeySerializer;
+
+impl ser::Serializer for KeySerializer {
+    type Ok = String;
+    type Error = SerializeError;
+
+    type SerializeSeq = Impossible<String, SerializeError>;
+    type SerializeTuple = Impossible<String, SerializeError>;
+    type SerializeTupleStruct = Impossible<String, SerializeError>;
+    type SerializeTupleVariant = Impossible<String, SerializeError>;
+    type SerializeMap = Impossible<String, SerializeError>;
+    type SerializeStruct = Impossible<String, SerializeError>;
+    type SerializeStructVariant = Impossible<String, SerializeError>;
+
+    fn serialize_bool(self, v: bool) -> Result<String> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<String> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<String> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<String> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<String> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<String> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<String> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<String> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<String> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<String> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<String> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_f32(self, _v: f32) -> Result<String> {
+        Err(SerializeError::InvalidKey("a float"))
+    }
+
+    fn serialize_f64(self, _v: f64) -> Result<String> {
+        Err(SerializeError::InvalidKey("a float"))
+    }
+
+    fn serialize_char(self, v: char) -> Result<String> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_str(self, v: &str) -> Result<String> {
+        Ok(v.to_owned())
+    }
+
+    fn serialize_bytes(self, _v: &[u8]) -> Result<String> {
+        Err(SerializeError::InvalidKey("bytes"))
+    }
+
+    fn serialize_none(self) -> Result<String> {
+        Err(SerializeError::InvalidKey("none"))
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<String> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<String> {
+        Err(SerializeError::InvalidKey("a unit"))
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<String> {
+        Err(SerializeError::InvalidKey("a unit struct"))
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<String> {
+        Ok(variant.to_owned())
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<String> {
+        if name == NUMBER_TOKEN {
+            return exact_number(value).map(|number| number.as_str().to_owned());
+        }
+
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<String> {
+        Err(SerializeError::InvalidKey("an enum variant with data"))
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq> {
+        Err(SerializeError::InvalidKey("a sequence"))
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple> {
+        Err(SerializeError::InvalidKey("a tuple"))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct> {
+        Err(SerializeError::InvalidKey("a tuple struct"))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant> {
+        Err(SerializeError::InvalidKey("an enum variant with data"))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap> {
+        Err(SerializeError::InvalidKey("a map"))
+    }
+
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeStruct> {
+        Err(SerializeError::InvalidKey("a struct"))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant> {
+        Err(SerializeError::InvalidKey("an enum variant with data"))
+    }
+}
+
+/// Separators for compact output.
+const COMPACT: Layout = Layout::Flat {
+    item: b",",
+    key: b":",
+};
+
+/// Separators for containers kept on one line in pretty output.
+const ONE_LINE: Layout = Layout::Flat {
+    item: b", ",
+    key: b": ",
+};
+
+#[derive(Clone, Copy)]
+enum Layout {
+    /// On a single line, with `item` between members and `key` between keys
+    /// and values.
+    Flat {
+        item: &'static [u8],
+        key: &'static [u8],
+    },
+    /// With each member of a container `depth` levels deep on its own line.
+    Pretty { indent: Indent, depth: usize },
+}
+
+/// A container that fits on one line only if no comma follows it, written
+/// both ways until it is known whether one does.
+struct Pending {
+    flat: Vec<u8>,
+    spread: Vec<u8>,
+}
+
+/// Writes `value` in pretty output, keeping it on one line if it fits within
+/// `max_width` when starting at `column`. When that depends on whether a
+/// comma follows and `comma` does not say, the value is returned unwritten.
+fn write_pretty<W: io::Write, T: Serialize + ?Sized>(
+    writer: &mut W,
+    value: &T,
+    options: &SerializerOptions,
+    indent: Indent,
+    depth: usize,
+    column: usize,
+    comma: Option<bool>,
+) -> Result<Option<Pending>> {
+    let spread = Layout::Pretty { indent, depth };
+
+    let layout = match options.max_width {
+        None => spread,
+        Some(max_width) => {
+            let mut limited = Limited(max_width.saturating_sub(column));
+
+            match value.serialize(JsonSerializer::new(&mut limited, options, ONE_LINE)) {
+                // Only writing past the end of the budget fails.
+                Err(SerializeError::Io(_)) => spread,
+                Err(e) => return Err(e),
+                Ok(()) if limited.0 > 0 || comma == Some(false) => ONE_LINE,
+                Ok(()) if comma == Some(true) => spread,
+                Ok(()) => {
+                    let mut pending = Pending {
+                        flat: Vec::new(),
+                        spread: Vec::new(),
+                    };
+                    value.serialize(JsonSerializer::new(&mut pending.flat, options, ONE_LINE))?;
+                    value.serialize(JsonSerializer::new(&mut pending.spread, options, spread))?;
+
+                    return Ok(Some(pending));
+                }
+            }
+        }
+    };
+
+    value.serialize(JsonSerializer::new(writer, options, layout))?;
+
+    Ok(None)
+}
+
+/// Writes any `Serialize` type as json straight to a writer, laid out like
+/// the crate's own [`Serializer`](super::Serializer) lays out a [`Value`].
+struct JsonSerializer<'s, W> {
+    writer: &'s mut W,
+    options: &'s SerializerOptions,
+    layout: Layout,
+}
+
+impl<'s, W: io::Write> JsonSerializer<'s, W> {
+    fn new(writer: &'s mut W, options: &'s SerializerOptions, layout: Layout) -> Self {
+        Self {
+            writer,
+            options,
+            layout,
+        }
+    }
+
+    fn write(self, bytes: &[u8]) -> Result<()> {
+        self.writer.write_all(bytes)?;
+        Ok(())
+    }
+
+    fn write_display(self, value: impl std::fmt::Display) -> Result<()> {
+        write!(self.writer, "{value}")?;
+        Ok(())
+    }
+
+    fn write_float(self, number: Option<Number<'static>>, v: f64) -> Result<()> {
+        self.write(float(number, v)?.as_str().as_bytes())
+    }
+
+    /// Writes the start of the object that wraps the data of `variant` in
+    /// pretty output, returning the column the data starts at.
+    fn open_variant(&mut self, variant: &str, indent: Indent, depth: usize) -> Result<usize> {
+        self.writer.write_all(b"{\n")?;
+        indent.write(self.writer, depth + 1)?;
+
+        Ok(indent.width() * (depth + 1) + write_key(self.writer, variant, self.options)?)
+    }
+
+    fn close_variant(&mut self, indent: Indent, depth: usize) -> Result<()> {
+        self.writer.write_all(b"\n")?;
+        indent.write(self.writer, depth)?;
+        self.writer.write_all(b"}")?;
+
+        Ok(())
+    }
+
+    /// Starts an array or object, wrapped in an object keyed by `variant` if
+    /// it holds the fields of an enum variant.
+    fn open(mut self, object: bool, variant: Option<&'static str>) -> Result<Compound<'s, W>> {
+        let (open, close): (&[u8], _) = if object { (b"{", b"}") } else { (b"[", b"]") };
+        let mut held = (object && self.options.sort_keys).then(|| Held::Sorted(Vec::new()));
+
+        match (variant, self.layout) {
+            (None, _) => self.writer.write_all(open)?,
+            (Some(variant), Layout::Flat { key, .. }) => {
+                self.writer.write_all(b"{")?;
+                write_string(self.writer, variant, self.options)?;
+                self.writer.write_all(key)?;
+                self.writer.write_all(open)?;
+            }
+            (Some(variant), Layout::Pretty { indent, depth }) => {
+                let column = self.open_variant(variant, indent, depth)?;
+                let value = if object {
+                    Value::Object(Object::new())
+                } else {
+                    Value::Array(Array::new())
+                };
+
+                held = Some(Held::Variant { value, column });
+            }
+        }
+
+        Ok(Compound {
+            writer: self.writer,
+            options: self.options,
+            layout: self.layout,
+            close,
+            variant: variant.is_some(),
+            first: true,
+            pending: None,
+            key: None,
+            held,
+        })
+    }
+}
+
+impl<'s, W: io::Write> ser::Serializer for JsonSerializer<'s, W> {
+    type Ok = ();
+    type Error = SerializeError;
+
+    type SerializeSeq = Compound<'s, W>;
+    type SerializeTuple = Compound<'s, W>;
+    type SerializeTupleStruct = Compound<'s, W>;
+    type SerializeTupleVariant = Compound<'s, W>;
+    type SerializeMap = Compound<'s, W>;
+    type SerializeStruct = Compound<'s, W>;
+    type SerializeStructVariant = Compound<'s, W>;
+
+    fn serialize_bool(self, v: bool) -> Result<()> {
+        self.write(if v { b"true" } else { b"false" })
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<()> {
+        self.write_display(v)
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<()> {
+        self.write_display(v)
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<()> {
+        self.write_display(v)
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<()> {
+        self.write_display(v)
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<()> {
+        self.write_display(v)
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<()> {
+        self.write_display(v)
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<()> {
+        self.write_display(v)
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<()> {
+        self.write_display(v)
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<()> {
+        self.write_display(v)
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<()> {
+        self.write_display(v)
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<()> {
+        self.write_float(Number::from_f32(v), v.into())
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<()> {
+        self.write_float(Number::from_f64(v), v)
+    }
+
+    fn serialize_char(self, v: char) -> Result<()> {
+        self.serialize_str(v.encode_utf8(&mut [0; 4]))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<()> {
+        write_string(self.writer, v, self.options)?;
+        Ok(())
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<()> {
+        ser::Serializer::collect_seq(self, v)
+    }
+
+    fn serialize_none(self) -> Result<()> {
+        self.write(b"null")
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<()> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<()> {
+        self.write(b"null")
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<()> {
+        self.write(b"null")
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<()> {
+        self.serialize_str(variant)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        if name == NUMBER_TOKEN {
+            return self.write(exact_number(value)?.as_str().as_bytes());
+        }
+
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        mut self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        match self.layout {
+            Layout::Flat { key, .. } => {
+                self.writer.write_all(b"{")?;
+                write_string(self.writer, variant, self.options)?;
+                self.writer.write_all(key)?;
+                value.serialize(JsonSerializer::new(self.writer, self.options, self.layout))?;
+                self.writer.write_all(b"}")?;
+            }
+            Layout::Pretty { indent, depth } => {
+                let column = self.open_variant(variant, indent, depth)?;
+                let (writer, options) = (&mut *self.writer, self.options);
+                write_pretty(
+                    writer,
+                    value,
+                    options,
+                    indent,
+                    depth + 1,
+                    column,
+                    Some(false),
+                )?;
+                self.close_variant(indent, depth)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Compound<'s, W>> {
+        self.open(false, None)
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Compound<'s, W>> {
+        self.open(false, None)
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, _len: usize) -> Result<Compound<'s, W>> {
+        self.open(false, None)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Compound<'s, W>> {
+        self.open(false, Some(variant))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Compound<'s, W>> {
+        self.open(true, None)
+    }
+
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Compound<'s, W>> {
+        self.open(true, None)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Compound<'s, W>> {
+        self.open(true, Some(variant))
+    }
+}
+
+/// Members that are collected as values before any of them is written.
+enum Held {
+    /// The members of an object, written in key order once all are known.
+    Sorted(Vec<(String, Value<'static>)>),
+    /// The fields of an enum variant in pretty output, which can only be
+    /// kept on one line once it is known how long they are. The fields start
+    /// at `column`.
+    Variant {
+        value: Value<'static>,
+        column: usize,
+    },
+}
+
+/// An array or object whose members are being written by a
+/// [`JsonSerializer`].
+struct Compound<'s, W> {
+    writer: &'s mut W,
+    options: &'s SerializerOptions,
+    layout: Layout,
+    close: &'static [u8],
+    /// Whether the container is wrapped in an object keyed by the enum
+    /// variant it belongs to.
+    variant: bool,
+    first: bool,
+    /// The previous member, if it is not written yet.
+    pending: Option<Pending>,
+    /// The key of the map entry whose value comes next.
+    key: Option<String>,
+    held: Option<Held>,
+}
+
+impl<W: io::Write> Compound<'_, W> {
+    fn member<T: Serialize + ?Sized>(&mut self, key: Option<&str>, member: &T) -> Result<()> {
+        let owned_key = || key.expect("object members have keys").to_owned();
+
+        match &mut self.held {
+            Some(Held::Sorted(members)) => members.push((owned_key(), to_value(member)?)),
+            Some(Held::Variant {
+                value: Value::Array(array),
+                ..
+            }) => array.push(to_value(member)?),
+            Some(Held::Variant {
+                value: Value::Object(object),
+                ..
+            }) => {
+                object.insert(owned_key(), to_value(member)?);
+            }
+            Some(Held::Variant { .. }) => unreachable!("variants hold an array or an object"),
+            None => self.write_member(key, member)?,
+        }
+
+        Ok(())
+    }
+
+    fn write_member<T: Serialize + ?Sized>(&mut self, key: Option<&str>, member: &T) -> Result<()> {
+        let writer = &mut *self.writer;
+
+        match self.layout {
+            Layout::Flat {
+                item,
+                key: separator,
+            } => {
+                if !self.first {
+                    writer.write_all(item)?;
+                }
+
+                if let Some(key) = key {
+                    write_string(writer, key, self.options)?;
+                    writer.write_all(separator)?;
+                }
+
+                member.serialize(JsonSerializer::new(writer, self.options, self.layout))?;
+            }
+            Layout::Pretty { indent, depth } => {
+                // A comma follows the previous member, so it does not fit on
+                // one line.
+                if let Some(pending) = self.pending.take() {
+                    writer.write_all(&pending.spread)?;
+                }
+
+                if !self.first {
+                    writer.write_all(b",")?;
+                }
+
+                writer.write_all(b"\n")?;
+                indent.write(writer, depth + 1)?;
+
+                let mut column = indent.width() * (depth + 1);
+
+                if let Some(key) = key {
+                    column += write_key(writer, key, self.options)?;
+                }
+
+                self.pending = write_pretty(
+                    writer,
+                    member,
+                    self.options,
+                    indent,
+                    depth + 1,
+                    column,
+                    None,
+                )?;
+            }
+        }
+
+        self.first = false;
+
+        Ok(())
+    }
+
+    fn end(mut self) -> Result<()> {
+        match self.held.take() {
+            Some(Held::Sorted(mut members)) => {
+                members.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+                for (key, member) in &members {
+                    self.write_member(Some(key), member)?;
+                }
+            }
+            Some(Held::Variant { value, column }) => {
+                let Layout::Pretty { indent, depth } = self.layout else {
+                    unreachable!("variants are only held in pretty output");
+                };
+
+                write_pretty(
+                    self.writer,
+                    &value,
+                    self.options,
+                    indent,
+                    depth + 1,
+                    column,
+                    Some(false),
+                )?;
+                self.writer.write_all(b"\n")?;
+                indent.write(self.writer, depth)?;
+                self.writer.write_all(b"}")?;
+
+                return Ok(());
+            }
+            None => {}
+        }
+
+        // Nothing follows the last member.
+        if let Some(pending) = self.pending.take() {
+            self.writer.write_all(&pending.flat)?;
+        }
+
+        if let Layout::Pretty { indent, depth } = self.layout
+            && !self.first
+        {
+            self.writer.write_all(b"\n")?;
+            indent.write(self.writer, depth)?;
+        }
+
+        self.writer.write_all(self.close)?;
+
+        if self.variant {
+            self.writer.write_all(b"}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<W: io::Write> ser::SerializeSeq for Compound<'_, W> {
+    type Ok = ();
+    type Error = SerializeError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        self.member(None, value)
+    }
+
+    fn end(self) -> Result<()> {
+        Compound::end(self)
+    }
+}
+
+impl<W: io::Write> ser::SerializeTuple for Compound<'_, W> {
+    type Ok = ();
+    type Error = SerializeError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        self.member(None, value)
+    }
+
+    fn end(self) -> Result<()> {
+        Compound::end(self)
+    }
+}
+
+impl<W: io::Write> ser::SerializeTupleStruct for Compound<'_, W> {
+    type Ok = ();
+    type Error = SerializeError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        self.member(None, value)
+    }
+
+    fn end(self) -> Result<()> {
+        Compound::end(self)
+    }
+}
+
+impl<W: io::Write> ser::SerializeTupleVariant for Compound<'_, W> {
+    type Ok = ();
+    type Error = SerializeError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        self.member(None, value)
+    }
+
+    fn end(self) -> Result<()> {
+        Compound::end(self)
+    }
+}
+
+impl<W: io::Write> ser::SerializeMap for Compound<'_, W> {
+    type Ok = ();
+    type Error = SerializeError;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<()> {
+        self.key = Some(key.serialize(KeySerializer)?);
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        let key = self
+            .key
+            .take()
+            .expect("serialize_value called before serialize_key");
+
+        self.member(Some(&key), value)
+    }
+
+    fn end(self) -> Result<()> {
+        Compound::end(self)
+    }
+}
+
+impl<W: io::Write> ser::SerializeStruct for Compound<'_, W> {
+    type Ok = ();
+    type Error = SerializeError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        self.member(Some(key), value)
+    }
+
+    fn end(self) -> Result<()> {
+        Compound::end(self)
+    }
+}
+
+impl<W: io::Write> ser::SerializeStructVariant for Compound<'_, W> {
+    type Ok = ();
+    type Error = SerializeError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        self.member(Some(key), value)
+    }
+
+    fn end(self) -> Result<()> {
+        Compound::end(self)
+    }
+}
+
+/// This crate's serializers write the exact text of the number. Other
+/// serializers see it as a string inside a newtype struct, since serde has no
+/// arbitrary precision number type.
+impl Serialize for Number<'_> {
+    fn serialize<S: ser::Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_newtype_struct(NUMBER_TOKEN, self.as_str())
+    }
+}
+
+impl Serialize for Array<'_> {
+    fn serialize<S: ser::Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+impl Serialize for Object<'_> {
+    fn serialize<S: ser::Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.collect_map(self.iter())
+    }
+}
+
+impl Serialize for Value<'_> {
+    fn serialize<S: ser::Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        match self {
+            Value::Object(object) => object.serialize(serializer),
+            Value::Array(array) => array.serialize(serializer),
+            Value::String(s) => serializer.serialize_str(s),
+            Value::Number(number) => number.serialize(serializer),
+            Value::Boolean(b) => serializer.serialize_bool(*b),
+            Value::Null => serializer.serialize_unit(),
+        }
+    }
+}
@@ -0,0 +1,240 @@
+use super::*;
+use crate::parser::{Array, DEEP, Number, Object, parse, parse_deep};
+
+#[test]
+fn scalars() {
+    [
+        ("null", "null"),
+        ("true", "true"),
+        ("false", "false"),
+        ("-1.50e+10", "-1.50e+10"),
+        (r#""text""#, r#""text""#),
+    ]
+    .iter()
+    .for_each(|(input, expected)| assert_eq!(to_string(&parse(input)), *expected));
+}
+
+#[test]
+fn containers_are_compact() {
+    let input = r#"{
+        "b": [1, 2, {"c": null}],
+        "a": {},
+        "e": []
+    }"#;
+
+    assert_eq!(
+        to_string(&parse(input)),
+        r#"{"b":[1,2,{"c":null}],"a":{},"e":[]}"#
+    );
+}
+
+#[test]
+fn strings_are_escaped() {
+    let value =
+        Value::String("quote\" backslash\\ slash/ \u{08}\u{0C}\n\r\t \u{01}\u{1F} é 😀".into());
+
+    assert_eq!(
+        to_string(&value),
+        r#""quote\" backslash\\ slash/ \b\f\n\r\t \u0001\u001f é 😀""#
+    );
+}
+
+#[test]
+fn non_ascii_can_be_escaped() {
+    let value = Value::String("é 😀".into());
+    let options = SerializerOptions {
+        escape_non_ascii: true,
+        ..Default::default()
+    };
+
+    assert_eq!(
+        to_string_with_options(&value, options),
+        r#""\u00e9 \ud83d\ude00""#
+    );
+}
+
+#[test]
+fn keys_are_escaped() {
+    let object: Object = [("a\"b", Value::Null)].into_iter().collect();
+
+    assert_eq!(to_string(&Value::Object(object)), r#"{"a\"b":null}"#);
+}
+
+#[test]
+fn round_trip() {
+    let input = r#"{"text":"line\nbreak \"quoted\" é","n":[0,-0.5,1e300],"nested":{"ok":true}}"#;
+    let value = parse(input);
+    let output = to_string(&value);
+
+    assert_eq!(parse(&output), value);
+    assert_eq!(value.to_string(), output);
+}
+
+#[test]
+fn writes_to_io_writer() {
+    let value = Value::Array(Array::from(vec![
+        Value::Number(Number::from(1)),
+        Value::Null,
+    ]));
+    let mut out = Vec::new();
+
+    to_writer(&mut out, &value).expect("writing failed");
+    assert_eq!(out, b"[1,null]");
+}
+
+#[test]
+fn very_deep_values() {
+    let value = parse_deep("[", "");
+
+    assert_eq!(to_string(&value), "[".repeat(DEEP) + &"]".repeat(DEEP));
+}
+
+const DOCUMENT: &str = r#"{"name": "json-parser", "tags": ["a", "b"], "empty": {}, "nested": {"list": [1, 2, 3], "deep": [{"x": null}]}}"#;
+
+#[test]
+fn pretty_expands_everything_without_max_width() {
+    let options = SerializerOptions {
+        max_width: None,
+        ..SerializerOptions::pretty()
+    };
+
+    assert_eq!(
+        to_string_with_options(&parse(DOCUMENT), options),
+        r#"{
+  "name": "json-parser",
+  "tags": [
+    "a",
+    "b"
+  ],
+  "empty": {},
+  "nested": {
+    "list": [
+      1,
+      2,
+      3
+    ],
+    "deep": [
+      {
+        "x": null
+      }
+    ]
+  }
+}"#
+    );
+}
+
+#[test]
+fn pretty_keeps_short_containers_on_one_line() {
+    let options = SerializerOptions {
+        max_width: Some(30),
+        ..SerializerOptions::pretty()
+    };
+
+    assert_eq!(
+        to_string_with_options(&parse(DOCUMENT), options),
+        r#"{
+  "name": "json-parser",
+  "tags": ["a", "b"],
+  "empty": {},
+  "nested": {
+    "list": [1, 2, 3],
+    "deep": [{"x": null}]
+  }
+}"#
+    );
+
+    assert_eq!(to_string_pretty(&parse("[1, [2, 3]]")), "[1, [2, 3]]");
+}
+
+#[test]
+fn pretty_width_accounts_for_key_and_comma() {
+    let input = r#"{"key": [1, 2], "k": 0}"#;
+    let options = |max_width| SerializerOptions {
+        max_width: Some(max_width),
+        ..SerializerOptions::pretty()
+    };
+
+    // `  "key": [1, 2],` is 16 columns wide.
+    assert!(to_string_with_options(&parse(input), options(16)).contains("\"key\": [1, 2],\n"));
+    assert!(to_string_with_options(&parse(input), options(15)).contains("\"key\": [\n"));
+}
+
+#[test]
+fn pretty_width_counts_characters() {
+    let input = r#"{"k": ["héllo", "wörld"]}"#;
+    let options = |max_width| SerializerOptions {
+        max_width: Some(max_width),
+        ..SerializerOptions::pretty()
+    };
+
+    // `  "k": ["héllo", "wörld"]` is 25 characters but 27 bytes wide.
+    assert!(to_string_with_options(&parse(input), options(25)).contains("[\"héllo\", \"wörld\"]"));
+    assert!(to_string_with_options(&parse(input), options(24)).contains("\"k\": [\n"));
+}
+
+#[test]
+fn pretty_indent_and_trailing_newline() {
+    let options = SerializerOptions {
+        indent: Some(Indent::Tabs(1)),
+        max_width: None,
+        trailing_newline: true,
+        ..Default::default()
+    };
+
+    assert_eq!(
+        to_string_with_options(&parse(r#"{"a": [true]}"#), options),
+        "{\n\t\"a\": [\n\t\ttrue\n\t]\n}\n"
+    );
+
+    let options = SerializerOptions {
+        indent: Some(Indent::Spaces(4)),
+        max_width: None,
+        ..Default::default()
+    };
+
+    assert_eq!(
+        to_string_with_options(&parse("[1]"), options),
+        "[\n    1\n]"
+    );
+}
+
+#[test]
+fn sort_keys() {
+    let input = r#"{"b": 1, "a": {"d": 2, "c": 3}}"#;
+    let options = SerializerOptions {
+        sort_keys: true,
+        ..Default::default()
+    };
+
+    assert_eq!(
+        to_string_with_options(&parse(input), options),
+        r#"{"a":{"c":3,"d":2},"b":1}"#
+    );
+
+    let options = SerializerOptions {
+        sort_keys: true,
+        max_width: None,
+        ..SerializerOptions::pretty()
+    };
+
+    assert_eq!(
+        to_string_with_options(&parse(input), options),
+        "{\n  \"a\": {\n    \"c\": 3,\n    \"d\": 2\n  },\n  \"b\": 1\n}"
+    );
+}
+
+#[test]
+fn display_alternate_is_pretty() {
+    let value = parse(r#"{"b": 1, "a": {"d": 2, "c": 3}}"#);
+
+    assert_eq!(format!("{value}"), r#"{"b":1,"a":{"d":2,"c":3}}"#);
+    assert_eq!(format!("{value:#}"), r#"{"b": 1, "a": {"d": 2, "c": 3}}"#);
+}
+
+#[test]
+fn pretty_round_trip() {
+    let value = parse(DOCUMENT);
+    let output = to_string_pretty(&value);
+
+    assert_eq!(parse(&output), value);
+}
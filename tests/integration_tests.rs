@@ -3,17 +3,16 @@ use std::fs;
 use std::path::Path;
 
 fn validate(path: &Path) -> Result<(), String> {
-    let input = fs::read(&path).expect("Error reading input");
+    let input = fs::read(path).expect("Error reading input");
 
     let mut lexer = Lexer::new(&input);
     let tokens = lexer.lex().map_err(|e| format!("[lexer] {e}"))?;
 
     let mut parser = Parser::new(tokens, &input);
-    match parser.parse() {
-        Some(Ok(_)) => Ok(()),
-        Some(Err(e)) => Err(format!("[parser]: {e}")),
-        None => Err("Empty input".to_string()),
-    }
+    parser
+        .parse()
+        .map(|_| ())
+        .map_err(|e| format!("[parser]: {e}"))
 }
 
 fn assert_valid(path: &str) {
@@ -45,6 +44,11 @@ mod step1_tests {
     fn test_step1_invalid() {
         assert_invalid("data/tests/step1/invalid.json");
     }
+
+    #[test]
+    fn test_step1_invalid2() {
+        assert_invalid("data/tests/step1/invalid2.json");
+    }
 }
 
 #[cfg(test)]
@@ -105,4 +109,44 @@ mod step4_tests {
     fn test_step4_invalid() {
         assert_invalid("data/tests/step4/invalid.json");
     }
+
+    #[test]
+    fn test_step4_invalid2() {
+        assert_invalid("data/tests/step4/invalid2.json");
+    }
+}
+
+#[cfg(test)]
+mod trailing_tests {
+    use super::*;
+
+    #[test]
+    fn test_trailing_invalid() {
+        assert_invalid("data/tests/trailing/invalid.json");
+    }
+}
+
+#[cfg(test)]
+mod cli_tests {
+    use std::process::Command;
+
+    fn validate(files: &[&str]) -> bool {
+        Command::new(env!("CARGO_BIN_EXE_json-parser"))
+            .args(files)
+            .output()
+            .expect("Failed to run json-parser")
+            .status
+            .success()
+    }
+
+    #[test]
+    fn test_exit_status_reflects_validity() {
+        assert!(validate(&["data/tests/step1/valid.json"]));
+        assert!(!validate(&["data/tests/step1/invalid.json"]));
+        assert!(!validate(&[
+            "data/tests/step1/valid.json",
+            "data/tests/step2/invalid.json"
+        ]));
+        assert!(!validate(&["data/tests/missing.json"]));
+    }
 }
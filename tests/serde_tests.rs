@@ -0,0 +1,470 @@
+#![cfg(feature = "serde")]
+
+use std::collections::{BTreeMap, HashMap};
+
+use json_parser::parser::DeserializeError;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct User<'a> {
+    name: &'a str,
+    age: u32,
+    email: Option<String>,
+    tags: Vec<String>,
+    #[serde(default)]
+    admin: bool,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+enum Shape {
+    Empty,
+    Circle(f64),
+    Point(i32, i32),
+    Rect { width: u32, height: u32 },
+}
+
+mod deserialize_tests {
+    use super::*;
+
+    #[test]
+    fn test_struct_borrows_strings() {
+        let input = br#"{"name": "ada", "age": 36, "email": null, "tags": ["a", "b"]}"#;
+        let user: User = json_parser::from_slice(input).unwrap();
+
+        assert_eq!(
+            user,
+            User {
+                name: "ada",
+                age: 36,
+                email: None,
+                tags: vec!["a".to_string(), "b".to_string()],
+                admin: false,
+            }
+        );
+
+        // The name points into the input rather than a copy.
+        let range = input.as_ptr_range();
+        assert!(range.contains(&user.name.as_ptr()));
+    }
+
+    #[test]
+    fn test_escaped_strings_are_owned() {
+        let value: String = json_parser::from_str(r#""line\nbreak""#).unwrap();
+        assert_eq!(value, "line\nbreak");
+
+        let error = json_parser::from_str::<&str>(r#""line\nbreak""#).unwrap_err();
+        assert!(matches!(error, DeserializeError::Custom { .. }));
+    }
+
+    #[test]
+    fn test_numbers() {
+        let values: (u8, i64, f64, u128, f32) = json_parser::from_str(
+            "[255, -9007199254740993, 1.5e3, 340282366920938463463374607431768211455, 2]",
+        )
+        .unwrap();
+
+        assert_eq!(values, (255, -9007199254740993, 1500.0, u128::MAX, 2.0));
+        assert!(json_parser::from_str::<u8>("256").is_err());
+        assert!(json_parser::from_str::<u32>("1.5").is_err());
+    }
+
+    #[test]
+    fn test_maps() {
+        let map: BTreeMap<String, Vec<Option<bool>>> =
+            json_parser::from_str(r#"{"a": [true, null], "b": []}"#).unwrap();
+        assert_eq!(map["a"], vec![Some(true), None]);
+        assert!(map["b"].is_empty());
+
+        let numbered: HashMap<u32, &str> =
+            json_parser::from_str(r#"{"1": "one", "2": "two"}"#).unwrap();
+        assert_eq!(numbered[&2], "two");
+    }
+
+    #[test]
+    fn test_enums() {
+        let shapes: Vec<Shape> = json_parser::from_str(
+            r#"["Empty", {"Circle": 1.5}, {"Point": [1, -2]}, {"Rect": {"width": 3, "height": 4}}]"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            shapes,
+            vec![
+                Shape::Empty,
+                Shape::Circle(1.5),
+                Shape::Point(1, -2),
+                Shape::Rect {
+                    width: 3,
+                    height: 4
+                },
+            ]
+        );
+        assert!(json_parser::from_str::<Shape>(r#"{"Circle": 1, "Empty": null}"#).is_err());
+    }
+
+    #[test]
+    fn test_tuple_length_is_checked() {
+        let error = json_parser::from_str::<(u8, u8)>("[1, 2, 3]").unwrap_err();
+        assert!(error.to_string().contains("more elements than expected"));
+
+        assert!(json_parser::from_str::<(u8, u8)>("[1]").is_err());
+    }
+
+    #[test]
+    fn test_errors_are_located() {
+        let input = "{\n  \"name\": \"ada\",\n  \"age\": \"old\",\n  \"tags\": []\n}";
+        let error = json_parser::from_str::<User>(input).unwrap_err();
+
+        let DeserializeError::Custom { line, col, .. } = error else {
+            panic!("Expected a custom error, got {error:?}");
+        };
+        assert_eq!((line, col), (3, 10));
+
+        let error = json_parser::from_str::<User>("{\"name\": \"ada\"}").unwrap_err();
+        assert!(error.to_string().contains("missing field `age`"));
+    }
+
+    #[test]
+    fn test_syntax_errors() {
+        assert!(matches!(
+            json_parser::from_str::<Vec<u8>>("[1, 2,]"),
+            Err(DeserializeError::Parser(_))
+        ));
+        assert!(matches!(
+            json_parser::from_str::<Vec<u8>>("[1] [2]"),
+            Err(DeserializeError::Parser(_))
+        ));
+        assert!(matches!(
+            json_parser::from_str::<Vec<u8>>("[01]"),
+            Err(DeserializeError::Lexer(_))
+        ));
+        assert!(matches!(
+            json_parser::from_str::<u8>(""),
+            Err(DeserializeError::Parser(_))
+        ));
+    }
+}
+
+mod serialize_tests {
+    use super::*;
+    use json_parser::serializer::{self, Indent, SerializeError, SerializerOptions};
+    use json_parser::{Lexer, Parser};
+    use std::cell::RefCell;
+    use std::io;
+    use std::rc::Rc;
+
+    #[derive(Serialize)]
+    struct Config {
+        name: &'static str,
+        retries: u8,
+        ratio: f64,
+        shapes: Vec<Shape>,
+        labels: BTreeMap<i32, bool>,
+        parent: Option<Box<Config>>,
+    }
+
+    fn config() -> Config {
+        Config {
+            name: "tab\there",
+            retries: 3,
+            ratio: 0.25,
+            shapes: vec![
+                Shape::Empty,
+                Shape::Circle(1.5),
+                Shape::Point(1, -2),
+                Shape::Rect {
+                    width: 3,
+                    height: 4,
+                },
+            ],
+            labels: BTreeMap::from([(-1, true), (2, false)]),
+            parent: None,
+        }
+    }
+
+    #[test]
+    fn test_compact() {
+        assert_eq!(
+            json_parser::to_string(&config()).unwrap(),
+            concat!(
+                r#"{"name":"tab\there","retries":3,"ratio":0.25,"#,
+                r#""shapes":["Empty",{"Circle":1.5},{"Point":[1,-2]},{"Rect":{"width":3,"height":4}}],"#,
+                r#""labels":{"-1":true,"2":false},"parent":null}"#
+            )
+        );
+    }
+
+    #[test]
+    fn test_matches_value_output() {
+        let compact = json_parser::to_string(&config()).unwrap();
+        let pretty = json_parser::to_string_pretty(&config()).unwrap();
+
+        let bytes = compact.as_bytes();
+        let tokens = Lexer::new(bytes).lex().unwrap();
+        let value = Parser::new(tokens, bytes).parse().unwrap();
+
+        assert_eq!(compact, serializer::to_string(&value));
+        assert_eq!(pretty, serializer::to_string_pretty(&value));
+
+        let mut written = Vec::new();
+        json_parser::to_writer_pretty(&mut written, &config()).unwrap();
+        assert_eq!(written, pretty.into_bytes());
+    }
+
+    #[test]
+    fn test_options_match_value_output() {
+        let nested = Config {
+            name: "naïve",
+            parent: Some(Box::new(config())),
+            ..config()
+        };
+
+        let compact = json_parser::to_string(&nested).unwrap();
+        let bytes = compact.as_bytes();
+        let tokens = Lexer::new(bytes).lex().unwrap();
+        let value = Parser::new(tokens, bytes).parse().unwrap();
+
+        for indent in [None, Some(Indent::Spaces(2)), Some(Indent::Tabs(1))] {
+            for max_width in (0..=120).map(Some).chain([None]) {
+                for (sort_keys, escape_non_ascii) in [(false, false), (true, true)] {
+                    let options = SerializerOptions {
+                        escape_non_ascii,
+                        sort_keys,
+                        indent,
+                        max_width,
+                        trailing_newline: true,
+                    };
+
+                    let mut written = Vec::new();
+                    json_parser::to_writer_with_options(&mut written, &nested, options.clone())
+                        .unwrap();
+
+                    assert_eq!(
+                        String::from_utf8(written).unwrap(),
+                        serializer::to_string_with_options(&value, options.clone()),
+                        "mismatch for {options:?}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_writes_while_serializing() {
+        /// Appends to a buffer that the values being serialized can see.
+        struct Shared(Rc<RefCell<Vec<u8>>>);
+
+        impl io::Write for Shared {
+            fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                self.0.borrow_mut().write(buf)
+            }
+
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        /// Serializes as the output written so far.
+        struct Written(Rc<RefCell<Vec<u8>>>);
+
+        impl Serialize for Written {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                let written = String::from_utf8(self.0.borrow().clone()).unwrap();
+                serializer.serialize_str(&written)
+            }
+        }
+
+        let buffer = Rc::new(RefCell::new(Vec::new()));
+        let values = [Written(buffer.clone()), Written(buffer.clone())];
+        json_parser::to_writer(Shared(buffer.clone()), &values).unwrap();
+
+        assert_eq!(
+            String::from_utf8(buffer.take()).unwrap(),
+            r#"["[","[\"[\","]"#
+        );
+    }
+
+    #[test]
+    fn test_floats() {
+        assert_eq!(json_parser::to_string(&0.1f32).unwrap(), "0.1");
+        assert_eq!(json_parser::to_string(&1.0f64).unwrap(), "1.0");
+        assert!(matches!(
+            json_parser::to_string(&f64::NAN),
+            Err(SerializeError::NonFinite(_))
+        ));
+        assert!(json_parser::to_value(&f32::INFINITY).is_err());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let shapes = vec![Shape::Point(-3, 7), Shape::Circle(0.5)];
+        let json = json_parser::to_string(&shapes).unwrap();
+
+        assert_eq!(json_parser::from_str::<Vec<Shape>>(&json).unwrap(), shapes);
+    }
+
+    #[test]
+    fn test_invalid_keys() {
+        let map = HashMap::from([(vec![1], 2)]);
+
+        assert!(matches!(
+            json_parser::to_string(&map),
+            Err(SerializeError::InvalidKey(_))
+        ));
+    }
+}
+
+mod value_tests {
+    use super::*;
+    use json_parser::parser::{DuplicateKeys, OwnedValue, ParserOptions, Value};
+    use json_parser::{Lexer, Parser};
+    use serde::de::DeserializeOwned;
+    use std::borrow::Cow;
+
+    fn parse(input: &str) -> Value<'_> {
+        let bytes = input.as_bytes();
+        let tokens = Lexer::new(bytes).lex().unwrap();
+
+        Parser::new(tokens, bytes).parse().unwrap()
+    }
+
+    #[derive(Deserialize)]
+    struct Borrowed<'a>(
+        #[serde(borrow, deserialize_with = "json_parser::parser::borrow_value")] Value<'a>,
+    );
+
+    fn assert_owned<T: DeserializeOwned>() {}
+
+    #[test]
+    fn test_deserialize_owned() {
+        assert_owned::<OwnedValue>();
+
+        let value: OwnedValue = json_parser::from_str(&String::from(r#"{"a": "b"}"#)).unwrap();
+        assert_eq!(value, parse(r#"{"a": "b"}"#));
+    }
+
+    #[test]
+    fn test_deserialize_borrows_strings() {
+        let input = r#"{"plain": "text", "escaped": "a\"b", "list": [1, -2, 3.5, true, null]}"#;
+        let Borrowed(value) = json_parser::from_str(input).unwrap();
+
+        assert_eq!(value, parse(input));
+
+        let Value::Object(object) = &value else {
+            panic!("Expected object, got {value:?}");
+        };
+        assert!(matches!(
+            object.get("plain"),
+            Some(Value::String(Cow::Borrowed("text")))
+        ));
+        assert!(matches!(
+            object.get("escaped"),
+            Some(Value::String(Cow::Owned(_)))
+        ));
+        assert!(matches!(object.iter().next(), Some(("plain", _))));
+    }
+
+    #[test]
+    fn test_serialize_matches_value_output() {
+        let value = parse(r#"{"b": [1, 2, {"c": "é"}], "a": -12, "f": 0.5, "n": null}"#);
+
+        assert_eq!(json_parser::to_string(&value).unwrap(), value.to_string());
+        assert_eq!(json_parser::to_value(&value).unwrap(), value);
+    }
+
+    #[test]
+    fn test_numbers_outside_f64() {
+        let value = parse("[18446744073709551616, -170141183460469231731687303715884105728]");
+        assert_eq!(json_parser::to_value(&value).unwrap(), value);
+
+        let value = parse("[1.50, 1e400, 123456789012345678901234567890.5]");
+        assert_eq!(
+            json_parser::to_string(&value).unwrap(),
+            "[1.50,1e400,123456789012345678901234567890.5]"
+        );
+        assert_eq!(json_parser::to_value(&value).unwrap(), value);
+    }
+
+    #[test]
+    fn test_deserialize_matches_parser() {
+        for input in [
+            "0.10000000000000000000001",
+            "[1e400, -0, 1.50, 18446744073709551616]",
+            r#"{"a": {"b": 1E+2}}"#,
+        ] {
+            let value: Value = json_parser::from_str(input).unwrap();
+
+            assert_eq!(value, parse(input));
+            assert_eq!(value.to_string(), parse(input).to_string());
+        }
+    }
+
+    #[test]
+    fn test_private_names_are_plain_keys() {
+        let input =
+            r#"{"$json_parser::private::Number": "5", "$json_parser::private::KeepAll": [1.50]}"#;
+        let bytes = input.as_bytes();
+
+        let value: Value = json_parser::from_str(input).unwrap();
+        assert_eq!(value, parse(input));
+        assert_eq!(json_parser::to_string(&value).unwrap(), value.to_string());
+        assert_eq!(json_parser::to_value(&value).unwrap(), value);
+
+        let options = ParserOptions {
+            duplicate_keys: DuplicateKeys::KeepAll,
+            ..ParserOptions::default()
+        };
+        let tokens = Lexer::new(bytes).lex().unwrap();
+        let mut parser = Parser::with_options(tokens, bytes, options);
+        assert_eq!(Value::deserialize(&mut parser).unwrap(), value);
+    }
+
+    #[test]
+    fn test_deserialize_duplicate_keys() {
+        let input = r#"{"a": 1, "b": 2, "a": 3}"#;
+        let bytes = input.as_bytes();
+
+        let error = json_parser::from_str::<Value>(input).unwrap_err();
+        let expected = Parser::new(Lexer::new(bytes).lex().unwrap(), bytes)
+            .parse()
+            .unwrap_err();
+        assert_eq!(error.to_string(), expected.to_string());
+
+        for duplicate_keys in [
+            DuplicateKeys::FirstWins,
+            DuplicateKeys::LastWins,
+            DuplicateKeys::KeepAll,
+        ] {
+            let options = ParserOptions {
+                duplicate_keys,
+                ..ParserOptions::default()
+            };
+            let tokens = Lexer::new(bytes).lex().unwrap();
+            let mut parser = Parser::with_options(tokens.clone(), bytes, options.clone());
+
+            assert_eq!(
+                Value::deserialize(&mut parser).unwrap().to_string(),
+                Parser::with_options(tokens, bytes, options)
+                    .parse()
+                    .unwrap()
+                    .to_string()
+            );
+        }
+    }
+
+    #[test]
+    fn test_typed_round_trip_through_value() {
+        let shapes = vec![
+            Shape::Empty,
+            Shape::Rect {
+                width: 1,
+                height: 2,
+            },
+        ];
+
+        let value = json_parser::to_value(&shapes).unwrap();
+        let json = json_parser::to_string(&value).unwrap();
+
+        assert_eq!(json_parser::from_str::<Vec<Shape>>(&json).unwrap(), shapes);
+    }
+}